@@ -1,6 +1,9 @@
 use bevy::prelude::*;
 
-use crate::{AppState, DebugSkipPlayerAction, GameEvent, GameObjectType, Player, PlayerEnd};
+use crate::{
+    AppState, DebugSkipPlayerAction, GameEvent, GameObjectType, Knockback, LogRoll, PendingTurn,
+    Player, PlayerEnd, Riding, player_translation, simulation::Outcome,
+};
 
 /// A 3D Axis-Aligned Bounding Box component.
 #[derive(Component, Debug, Copy, Clone, PartialEq)] // Added PartialEq for assert_eq!
@@ -72,40 +75,116 @@ impl LLAabb3d {
     }
 }
 
+/// Knocks the player back when a log reaches them, or ends the game once they are out
+/// of lives. Whether the player was hit is up to the simulation, the collisions only
+/// decide when during the roll it happens. A player carried by a log is only hit once
+/// the roll is over.
 pub fn player_collision_handling_system(
-    mut query: Query<Entity, With<Player>>,
+    query: Query<(Entity, &Transform, Has<Riding>), (With<Player>, Without<PlayerEnd>)>,
     mut collision_event: EventReader<CollisionEvent>,
     mut next_app_state: ResMut<NextState<AppState>>,
     mut commands: Commands,
     debug: Res<DebugSkipPlayerAction>,
+    pending_turn: Res<PendingTurn>,
+    mut knocked_back: Local<bool>,
 ) {
+    if pending_turn.is_changed() {
+        *knocked_back = false;
+    }
     if debug.skip_player_collision {
         return;
     }
-    for _ev in collision_event.read() {
-        let Ok(entity) = query.single_mut() else {
+    let hits = collision_event
+        .read()
+        .filter(|event| matches!(event, CollisionEvent::PlayerLog))
+        .count();
+    let Some(turn) = pending_turn.0.as_ref() else {
+        return;
+    };
+    if hits == 0 || !turn.outcome.is_hit() {
+        return;
+    }
+    let Ok((entity, transform, riding)) = query.single() else {
+        return;
+    };
+    if riding {
+        return;
+    }
+    if turn.outcome == Outcome::Hit {
+        if *knocked_back {
             return;
+        }
+        *knocked_back = true;
+        commands.entity(entity).insert_if_new(Knockback {
+            start_position: transform.translation,
+            target_position: player_translation(turn.board.player, false),
+            timer: Timer::from_seconds(0.3, TimerMode::Once),
+        });
+        return;
+    }
+    commands
+        .entity(entity)
+        .insert_if_new(PlayerEnd(Timer::from_seconds(1.6, TimerMode::Once)));
+    commands.trigger(GameEvent::Over);
+    next_app_state.set(AppState::EndGame);
+}
+
+/// Wobbles a rock after a log ran into it.
+#[derive(Component)]
+pub struct RockShake(pub Timer);
+
+/// Shakes the rocks that stopped a log this turn. Where the logs stop is up to the
+/// simulation, a log that was already stopped against the rock doesn't shake it again.
+pub fn log_rock_collision_handling_system(
+    mut collision_event: EventReader<CollisionEvent>,
+    log_query: Query<&LogRoll>,
+    mut commands: Commands,
+) {
+    for event in collision_event.read() {
+        let CollisionEvent::LogRock { log, rock } = event else {
+            continue;
         };
-        commands
-            .entity(entity)
-            .insert_if_new(PlayerEnd(Timer::from_seconds(1.6, TimerMode::Once)));
-        commands.trigger(GameEvent::Over);
-        next_app_state.set(AppState::EndGame);
+        let Ok(log_roll) = log_query.get(*log) else {
+            continue;
+        };
+        if log_roll.start_position != log_roll.target_position {
+            commands
+                .entity(*rock)
+                .insert_if_new(RockShake(Timer::from_seconds(0.3, TimerMode::Once)));
+        }
+    }
+}
+
+pub fn shake_rocks(
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut Transform, &mut RockShake)>,
+    mut commands: Commands,
+) {
+    for (entity, mut transform, mut shake) in query.iter_mut() {
+        shake.0.tick(time.delta());
+        let angle = (shake.0.fraction() * 6.0 * std::f32::consts::PI).sin() * 0.15;
+        transform.rotation = Quat::from_rotation_z(angle * shake.0.fraction_remaining());
+        if shake.0.finished() {
+            transform.rotation = Quat::IDENTITY;
+            commands.entity(entity).remove::<RockShake>();
+        }
     }
 }
 
 #[derive(Event)]
 pub enum CollisionEvent {
     PlayerLog,
-    // LogRock,
+    LogRock { log: Entity, rock: Entity },
 }
 
 pub fn collision_detection_system(
-    query: Query<(&GameObjectType, &LLAabb3d)>,
+    query: Query<(Entity, &GameObjectType, &LLAabb3d)>,
     mut collision_event: EventWriter<CollisionEvent>,
 ) {
     let mut combinations = query.iter_combinations();
-    while let Some([(got_a, aabb_a), (got_b, aabb_b)]) = combinations.fetch_next() {
+    while let Some([(entity_a, got_a, aabb_a), (entity_b, got_b, aabb_b)]) =
+        combinations.fetch_next()
+    {
         if aabb_a.intersects(aabb_b) && got_a != got_b {
             // Handle collision
             info!(
@@ -113,15 +192,22 @@ pub fn collision_detection_system(
                 (got_a, aabb_a),
                 (got_b, aabb_b)
             );
-            match got_a {
-                GameObjectType::Player => {
+            match (got_a, got_b) {
+                (GameObjectType::Player, GameObjectType::Log)
+                | (GameObjectType::Log, GameObjectType::Player) => {
                     collision_event.write(CollisionEvent::PlayerLog);
                 }
-                _ => (),
-            }
-            match got_b {
-                GameObjectType::Player => {
-                    collision_event.write(CollisionEvent::PlayerLog);
+                (GameObjectType::Log, GameObjectType::Rock) => {
+                    collision_event.write(CollisionEvent::LogRock {
+                        log: entity_a,
+                        rock: entity_b,
+                    });
+                }
+                (GameObjectType::Rock, GameObjectType::Log) => {
+                    collision_event.write(CollisionEvent::LogRock {
+                        log: entity_b,
+                        rock: entity_a,
+                    });
                 }
                 _ => (),
             }
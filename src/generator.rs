@@ -0,0 +1,295 @@
+//! Builds random levels from a seed. A level is only handed out once the solver has
+//! beaten it, so every seed gives a playable level or nothing.
+
+use bevy::{
+    platform::collections::HashSet,
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task, block_on, futures_lite::future},
+};
+use bevy_rand::prelude::WyRand;
+use rand::{Rng, SeedableRng};
+
+use crate::{
+    AppState, Game,
+    level::{
+        BirdBehavior, BirdKind, BirdSpawn, Level, LevelDiagnostic, LevelScoring, LogSequence,
+        SpawnStep,
+    },
+    score::PAR_BONUS,
+    simulation::{LOG_LENGTH, Simulation},
+    solver::{Solution, solve},
+};
+
+/// The generated levels played after the campaign, kept so a lost level can be rebuilt.
+#[derive(Resource, Default)]
+pub struct GeneratedLevels(pub Vec<Level>);
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GeneratorSettings {
+    pub seed: u64,
+    pub board_size: UVec2,
+    /// Chance of each pattern step spawning a log, from 0 to 1
+    pub spawn_density: f32,
+    pub bird_count: u32,
+    /// Levels to try before giving up on the seed
+    pub max_attempts: u32,
+}
+
+impl Default for GeneratorSettings {
+    fn default() -> Self {
+        Self {
+            seed: 0,
+            board_size: UVec2::new(12, 12),
+            spawn_density: 0.35,
+            bird_count: 3,
+            max_attempts: 50,
+        }
+    }
+}
+
+impl GeneratorSettings {
+    /// Settings for the `round`th generated level after the campaign, every round is a
+    /// little denser and has more birds.
+    pub fn endless(seed: u64, round: u32) -> Self {
+        Self {
+            seed,
+            spawn_density: (0.3 + 0.05 * round as f32).min(0.6),
+            bird_count: (2 + round / 2).min(5),
+            ..default()
+        }
+    }
+}
+
+pub struct GeneratedLevel {
+    pub level: Level,
+    /// The solver's shortest way through the level
+    pub solution: Solution,
+    /// Levels built before one could be beaten
+    pub attempts: u32,
+}
+
+/// A level being generated on the [`AsyncComputeTaskPool`], the attempts can take many
+/// frames. It is played as soon as it is done.
+#[derive(Component)]
+pub struct LevelGeneration {
+    seed: u64,
+    task: Task<Option<GeneratedLevel>>,
+}
+
+impl LevelGeneration {
+    /// Starts generating the level played after the last one of `game`.
+    pub fn start(game: &Game, generated: &GeneratedLevels, seed: u64) -> Self {
+        let settings = GeneratorSettings {
+            board_size: game.board_size_as_ivec2(),
+            ..GeneratorSettings::endless(seed, generated.0.len() as u32)
+        };
+        let task = AsyncComputeTaskPool::get().spawn(async move { generate_level(&settings) });
+        Self { seed, task }
+    }
+}
+
+/// Moves on to the generated level once it is done. Without one the campaign is over.
+pub fn finish_level_generation(
+    mut generations: Query<(Entity, &mut LevelGeneration)>,
+    mut game: ResMut<Game>,
+    mut generated: ResMut<GeneratedLevels>,
+    app_state: Res<State<AppState>>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut commands: Commands,
+) {
+    for (entity, mut generation) in generations.iter_mut() {
+        let Some(level) = block_on(future::poll_once(&mut generation.task)) else {
+            continue;
+        };
+        commands.entity(entity).despawn();
+        let campaign_complete = *app_state.get() == AppState::CampaignComplete;
+        match level {
+            Some(level) => game.add_generated_level(&mut generated, generation.seed, level),
+            None => {
+                warn!(
+                    "Seed {} didn't give a level that can be beaten",
+                    generation.seed
+                );
+                // The last level was already banked, there is nothing to move on to
+                if campaign_complete {
+                    continue;
+                }
+            }
+        }
+        // The board itself is rebuilt by `cleanup_game` when leaving the state
+        if game.next_level() {
+            info!("Advancing to level {}", game.current_level_index() + 1);
+            next_app_state.set(AppState::InGame);
+        } else if !campaign_complete {
+            info!("Last level won, campaign complete!");
+            next_app_state.set(AppState::CampaignComplete);
+        }
+    }
+}
+
+/// Builds levels from `settings.seed` until one passes validation and can be beaten.
+/// The same settings always give the same level.
+pub fn generate_level(settings: &GeneratorSettings) -> Option<GeneratedLevel> {
+    let mut rng = WyRand::seed_from_u64(settings.seed);
+    let game = Game {
+        board_size_x: settings.board_size.x,
+        board_size_y: settings.board_size.y,
+        start: IVec2::new(settings.board_size.x as i32 / 2, 0),
+        ..default()
+    };
+
+    for attempt in 1..=settings.max_attempts {
+        let mut level = random_level(&mut rng, settings, game.start);
+        if level.validate(&game).iter().any(LevelDiagnostic::is_error) {
+            continue;
+        }
+        if let Some(solution) = solve(&Simulation::for_level(&game, &level)) {
+            level.scoring = scoring(&level, solution.turns() as u32);
+            return Some(GeneratedLevel {
+                level,
+                solution,
+                attempts: attempt,
+            });
+        }
+    }
+    None
+}
+
+/// Par is a few turns over the solver's, one star for the birds, two for making par
+/// and three for streaks on top.
+fn scoring(level: &Level, turns: u32) -> LevelScoring {
+    let birds: u32 = level.birds.iter().map(|bird| bird.kind.points()).sum();
+    LevelScoring {
+        par: Some(turns + turns / 4),
+        stars: [birds, birds + PAR_BONUS, birds * 2 + PAR_BONUS],
+    }
+}
+
+/// One sequence per lane of `LOG_LENGTH` columns and birds anywhere but the start row.
+fn random_level(rng: &mut WyRand, settings: &GeneratorSettings, start: IVec2) -> Level {
+    let size = settings.board_size.as_ivec2();
+    let density = settings.spawn_density.clamp(0.0, 1.0) as f64;
+    let lanes = (size.x + LOG_LENGTH - 1) / LOG_LENGTH;
+
+    let seq = (0..lanes)
+        .map(|lane| {
+            let base = (lane * LOG_LENGTH + LOG_LENGTH / 2).min(size.x - 1);
+            let period = rng.random_range(4..=8);
+            let steps = (0..period)
+                .map(|_| {
+                    if !rng.random_bool(density) {
+                        return SpawnStep::default();
+                    }
+                    let column = rng.random_range((base - 1).max(0)..=(base + 1).min(size.x - 1));
+                    SpawnStep {
+                        nth_step: 1,
+                        offset_x: column - base,
+                        offset_z: rng.random_bool(0.2) as i32,
+                    }
+                })
+                .collect();
+            let step_length = if rng.random_bool(density) { 2 } else { 1 };
+            LogSequence::new(steps, step_length, base)
+        })
+        .collect();
+
+    // Every tile off the start row can hold a bird
+    let bird_count = settings
+        .bird_count
+        .min(((size.y - 1) * size.x).max(0) as u32);
+    let mut taken = HashSet::new();
+    let mut birds = Vec::new();
+    while birds.len() < bird_count as usize {
+        let pos = IVec2::new(rng.random_range(0..size.x), rng.random_range(1..size.y));
+        if pos != start && taken.insert(pos) {
+            birds.push(BirdSpawn {
+                pos,
+                kind: BirdKind::Common,
+                name: None,
+                behavior: BirdBehavior::Still,
+            });
+        }
+    }
+
+    Level {
+        seq,
+        birds,
+        ..default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Everything that describes a level, for comparing two of them.
+    fn describe(level: &Level) -> (Vec<(String, u32, i32)>, Vec<IVec2>) {
+        (
+            level
+                .seq
+                .iter()
+                .map(|seq| {
+                    (
+                        seq.pattern(),
+                        seq.get_step_length(),
+                        seq.spawn_offset_x_base,
+                    )
+                })
+                .collect(),
+            level.birds.iter().map(|bird| bird.pos).collect(),
+        )
+    }
+
+    #[test]
+    fn test_same_seed_same_level() {
+        let settings = GeneratorSettings {
+            seed: 42,
+            ..default()
+        };
+        let first = generate_level(&settings).unwrap();
+        let second = generate_level(&settings).unwrap();
+        assert_eq!(describe(&first.level), describe(&second.level));
+        assert_eq!(first.solution, second.solution);
+
+        let other = generate_level(&GeneratorSettings {
+            seed: 43,
+            ..default()
+        })
+        .unwrap();
+        assert_ne!(describe(&first.level), describe(&other.level));
+    }
+
+    #[test]
+    fn test_generated_levels_can_be_beaten() {
+        for seed in 0..5 {
+            let settings = GeneratorSettings::endless(seed, seed as u32);
+            let generated = generate_level(&settings).unwrap();
+            assert_eq!(generated.level.birds.len(), settings.bird_count as usize);
+            let game = Game {
+                levels: vec![generated.level],
+                ..default()
+            };
+            let diagnostics = game.levels[0].validate(&game);
+            assert!(!diagnostics.iter().any(LevelDiagnostic::is_error));
+            assert_eq!(
+                solve(&Simulation::new(&game)).map(|solution| solution.turns()),
+                Some(generated.solution.turns())
+            );
+        }
+    }
+
+    #[test]
+    fn test_smaller_board() {
+        let settings = GeneratorSettings {
+            seed: 7,
+            board_size: UVec2::new(7, 9),
+            bird_count: 2,
+            ..default()
+        };
+        let level = generate_level(&settings).unwrap().level;
+        assert_eq!(level.seq.len(), 2);
+        for bird in level.birds.iter() {
+            assert!(bird.pos.x < 7 && bird.pos.y < 9, "{bird:?}");
+        }
+    }
+}
@@ -0,0 +1,1492 @@
+use std::{collections::VecDeque, fmt};
+
+use bevy::{
+    platform::collections::{HashMap, HashSet},
+    prelude::*,
+    tasks::{AsyncComputeTaskPool, Task, block_on, futures_lite::future},
+};
+use bevy_asset_loader::asset_collection::AssetCollection;
+
+use crate::{
+    Direction, Game,
+    simulation::{JUMP_TILES, LOG_LENGTH, LOG_ROLL_TILES, Simulation},
+    solver::{Solution, solve},
+    spawn_pattern::{format_pattern, parse_pattern},
+};
+
+/// The campaign levels, listed in order in `levels/campaign.assets.ron`.
+#[derive(AssetCollection, Resource)]
+pub struct LevelAssets {
+    #[asset(key = "levels", collection(typed))]
+    pub levels: Vec<Handle<Level>>,
+}
+
+impl LevelAssets {
+    /// Returns a fresh copy of every loaded level in campaign order.
+    pub fn clone_levels(&self, levels: &Assets<Level>) -> Vec<Level> {
+        self.levels
+            .iter()
+            .filter_map(|handle| levels.get(handle))
+            .cloned()
+            .collect()
+    }
+}
+
+#[derive(Clone, Default, Reflect, serde::Deserialize, Asset)]
+pub struct Level {
+    pub seq: Vec<LogSequence>,
+    /// Where the birds are placed when the level starts
+    pub birds: Vec<BirdSpawn>,
+    /// Tiles the player can't walk onto, logs stop in front of them
+    #[serde(default)]
+    pub rocks: Vec<IVec2>,
+    /// What the tiles are made of
+    #[serde(default)]
+    pub tiles: TileGrid,
+    /// Tile the rescued birds are carried to, without one they are banked as soon as
+    /// they are rescued
+    #[serde(default)]
+    pub nest: Option<IVec2>,
+    #[serde(default)]
+    pub scoring: LevelScoring,
+    /// Hits the player can take on this level, the game's `lives` without one
+    #[serde(default)]
+    pub lives: Option<u32>,
+    /// Bird tile -> bird entity, built from `birds` when the level is spawned
+    #[serde(skip)]
+    pub bird_map: HashMap<IVec3, Entity>,
+}
+
+/// Targets for the score of a level.
+#[derive(Clone, Debug, Default, PartialEq, Reflect, serde::Deserialize)]
+pub struct LevelScoring {
+    /// Turns to beat the level in for a bonus
+    #[serde(default)]
+    pub par: Option<u32>,
+    /// Points needed on the level for one, two and three stars
+    #[serde(default)]
+    pub stars: [u32; 3],
+}
+
+impl Level {
+    /// Checks the level data against the board of `game`.
+    pub fn validate(&self, game: &Game) -> Vec<LevelDiagnostic> {
+        let mut diagnostics = Vec::new();
+        let on_board = |pos: IVec2| {
+            pos.x >= 0
+                && pos.x < game.board_size_x as i32
+                && pos.y >= 0
+                && pos.y < game.board_size_y as i32
+        };
+
+        for (seq_idx, seq) in self.seq.iter().enumerate() {
+            if seq.steps.is_empty() {
+                diagnostics.push(LevelDiagnostic::EmptySequence { seq: seq_idx });
+                continue;
+            }
+
+            if seq.speed == 0 {
+                diagnostics.push(LevelDiagnostic::ZeroSpeed { seq: seq_idx });
+            }
+            if seq.length == 0 {
+                diagnostics.push(LevelDiagnostic::ZeroLength { seq: seq_idx });
+            }
+
+            let edge_len = seq.edge.len(game.board_size_as_ivec2().as_ivec2());
+            for (step, spawn) in seq.steps.iter().enumerate() {
+                if !spawn.spawns() {
+                    continue;
+                }
+                let column = seq.spawn_offset_x_base + spawn.offset_x;
+                if column < 0 || column >= edge_len {
+                    diagnostics.push(LevelDiagnostic::SpawnColumnOutOfBounds {
+                        seq: seq_idx,
+                        step,
+                        column,
+                    });
+                }
+            }
+        }
+
+        for rock in self.rocks.iter() {
+            if !on_board(*rock) {
+                diagnostics.push(LevelDiagnostic::RockOffBoard { pos: *rock });
+            } else if *rock == game.start {
+                diagnostics.push(LevelDiagnostic::RockOnStart { pos: *rock });
+            }
+        }
+
+        if self.tiles.get(game.start) == TileKind::Water {
+            diagnostics.push(LevelDiagnostic::WaterOnStart { pos: game.start });
+        }
+
+        if !self.scoring.stars.is_sorted() {
+            diagnostics.push(LevelDiagnostic::StarsOutOfOrder {
+                stars: self.scoring.stars,
+            });
+        }
+
+        let reachable = reachable_tiles(game.start, |pos| {
+            on_board(pos) && !self.rocks.contains(&pos) && self.tiles.get(pos) != TileKind::Water
+        });
+        let mut seen = HashSet::new();
+        for bird in self.birds.iter() {
+            if !seen.insert(bird.pos) {
+                diagnostics.push(LevelDiagnostic::DuplicateBird { pos: bird.pos });
+            } else if !on_board(bird.pos) {
+                diagnostics.push(LevelDiagnostic::BirdOffBoard { pos: bird.pos });
+            } else if !reachable.contains(&bird.pos) {
+                diagnostics.push(LevelDiagnostic::BirdUnreachable { pos: bird.pos });
+            }
+
+            match &bird.behavior {
+                BirdBehavior::Hop { every, moves } if *every == 0 || moves.is_empty() => {
+                    diagnostics.push(LevelDiagnostic::BirdNeverHops { pos: bird.pos });
+                }
+                BirdBehavior::Circle { path } => {
+                    for tile in path.iter() {
+                        if !on_board(*tile) || self.rocks.contains(tile) {
+                            diagnostics.push(LevelDiagnostic::BirdPathBlocked {
+                                pos: bird.pos,
+                                tile: *tile,
+                            });
+                        }
+                    }
+                    // Every leg, including the one back to the bird's own tile
+                    let from = std::iter::once(bird.pos).chain(path.iter().copied());
+                    let to = path.iter().copied().chain(std::iter::once(bird.pos));
+                    for (from, to) in from.zip(to) {
+                        if (to - from).abs().element_sum() > 1 {
+                            diagnostics.push(LevelDiagnostic::BirdPathGap {
+                                pos: bird.pos,
+                                from,
+                                to,
+                            });
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if let Some(nest) = self.nest.filter(|nest| !reachable.contains(nest)) {
+            diagnostics.push(LevelDiagnostic::NestUnreachable { pos: nest });
+        }
+
+        diagnostics
+    }
+}
+
+/// Flood fills the board from `start`, returning every tile the player can walk or jump
+/// to. Jumps clear whatever lies between, so only their landing tile has to be walkable.
+fn reachable_tiles(start: IVec2, walkable: impl Fn(IVec2) -> bool) -> HashSet<IVec2> {
+    let mut reachable = HashSet::new();
+    if !walkable(start) {
+        return reachable;
+    }
+    let mut queue = VecDeque::from([start]);
+    reachable.insert(start);
+    while let Some(pos) = queue.pop_front() {
+        for dir in [IVec2::X, IVec2::NEG_X, IVec2::Y, IVec2::NEG_Y] {
+            for next in [pos + dir, pos + dir * JUMP_TILES] {
+                if walkable(next) && reachable.insert(next) {
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+    reachable
+}
+
+/// A problem found by [`Level::validate`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LevelDiagnostic {
+    /// A sequence without any steps, it has no period to repeat
+    EmptySequence {
+        seq: usize,
+    },
+    /// The logs would never move
+    ZeroSpeed {
+        seq: usize,
+    },
+    ZeroLength {
+        seq: usize,
+    },
+    /// A spawning step puts its log outside the board, `column` is along the spawn edge
+    SpawnColumnOutOfBounds {
+        seq: usize,
+        step: usize,
+        column: i32,
+    },
+    BirdOffBoard {
+        pos: IVec2,
+    },
+    /// The player can't walk from the start tile to the bird
+    BirdUnreachable {
+        pos: IVec2,
+    },
+    /// More than one bird on the same tile, only one of them can be rescued
+    DuplicateBird {
+        pos: IVec2,
+    },
+    RockOffBoard {
+        pos: IVec2,
+    },
+    /// The player would start inside the rock
+    RockOnStart {
+        pos: IVec2,
+    },
+    /// A hopping bird with no moves or an `every` of 0
+    BirdNeverHops {
+        pos: IVec2,
+    },
+    /// A circling bird's path goes off the board or onto a rock
+    BirdPathBlocked {
+        pos: IVec2,
+        tile: IVec2,
+    },
+    /// A circling bird's path has a leg between tiles that aren't next to each other
+    BirdPathGap {
+        pos: IVec2,
+        from: IVec2,
+        to: IVec2,
+    },
+    /// The nest is off the board, on a rock or water, or can't be walked to from the start
+    NestUnreachable {
+        pos: IVec2,
+    },
+    /// The player would start in the water and drown on the first turn
+    WaterOnStart {
+        pos: IVec2,
+    },
+    /// A star needs fewer points than the one before it
+    StarsOutOfOrder {
+        stars: [u32; 3],
+    },
+}
+
+impl LevelDiagnostic {
+    /// Errors make a level unplayable, everything else is only worth a warning.
+    pub fn is_error(&self) -> bool {
+        !matches!(
+            self,
+            LevelDiagnostic::BirdNeverHops { .. } | LevelDiagnostic::StarsOutOfOrder { .. }
+        )
+    }
+}
+
+impl fmt::Display for LevelDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LevelDiagnostic::EmptySequence { seq } => write!(f, "sequence {seq} has no steps"),
+            LevelDiagnostic::ZeroSpeed { seq } => write!(f, "sequence {seq} has a speed of 0"),
+            LevelDiagnostic::ZeroLength { seq } => write!(f, "sequence {seq} has a length of 0"),
+            LevelDiagnostic::SpawnColumnOutOfBounds { seq, step, column } => write!(
+                f,
+                "sequence {seq} spawns a log off the board at step {step} (column {column})"
+            ),
+            LevelDiagnostic::BirdOffBoard { pos } => write!(f, "bird at {pos} is off the board"),
+            LevelDiagnostic::BirdUnreachable { pos } => {
+                write!(f, "bird at {pos} can't be reached from the start")
+            }
+            LevelDiagnostic::DuplicateBird { pos } => {
+                write!(f, "more than one bird at {pos}")
+            }
+            LevelDiagnostic::RockOffBoard { pos } => write!(f, "rock at {pos} is off the board"),
+            LevelDiagnostic::RockOnStart { pos } => {
+                write!(f, "rock at {pos} is on the player's start tile")
+            }
+            LevelDiagnostic::BirdNeverHops { pos } => write!(f, "bird at {pos} never hops"),
+            LevelDiagnostic::BirdPathBlocked { pos, tile } => {
+                write!(f, "bird at {pos} can't fly to {tile}")
+            }
+            LevelDiagnostic::BirdPathGap { pos, from, to } => {
+                write!(f, "bird at {pos} can't fly from {from} to {to} in one turn")
+            }
+            LevelDiagnostic::NestUnreachable { pos } => {
+                write!(f, "nest at {pos} can't be reached from the start")
+            }
+            LevelDiagnostic::WaterOnStart { pos } => {
+                write!(f, "water at {pos} is on the player's start tile")
+            }
+            LevelDiagnostic::StarsOutOfOrder { stars } => {
+                write!(f, "star points {stars:?} don't go up")
+            }
+        }
+    }
+}
+
+/// What a tile of the board is made of.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect)]
+pub enum TileKind {
+    #[default]
+    Ground,
+    /// Drowns the player, unless they stand on a log
+    Water,
+    /// Walking onto it gets the player stuck, their next turn is lost
+    Mud,
+    /// A player walking onto it slides on until something stops them
+    Ice,
+    /// Pushes the player a tile along at the end of every turn
+    Conveyor(Direction),
+}
+
+impl TileKind {
+    /// The kind written as `ch` in a [`TileGrid`].
+    pub fn from_char(ch: char) -> Option<TileKind> {
+        Some(match ch {
+            '.' => TileKind::Ground,
+            '~' => TileKind::Water,
+            'm' => TileKind::Mud,
+            'i' => TileKind::Ice,
+            'N' => TileKind::Conveyor(Direction::North),
+            'E' => TileKind::Conveyor(Direction::East),
+            'S' => TileKind::Conveyor(Direction::South),
+            'W' => TileKind::Conveyor(Direction::West),
+            _ => return None,
+        })
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            TileKind::Ground => Color::linear_rgb(0.51, 0.54, 0.075),
+            TileKind::Water => Color::linear_rgb(0.05, 0.2, 0.6),
+            TileKind::Mud => Color::linear_rgb(0.22, 0.13, 0.05),
+            TileKind::Ice => Color::linear_rgb(0.7, 0.88, 0.95),
+            TileKind::Conveyor(_) => Color::linear_rgb(0.3, 0.3, 0.33),
+        }
+    }
+}
+
+/// The [`TileKind`] of every tile. A level file lists it as one string per row, starting
+/// with row 0 where the player starts, and one character per tile: `.` ground, `~`
+/// water, `m` mud, `i` ice and `N`, `E`, `S` or `W` for a conveyor pushing that way.
+/// Tiles left out are ground.
+#[derive(Clone, Debug, Default, PartialEq, Reflect, serde::Deserialize)]
+#[serde(try_from = "Vec<String>")]
+pub struct TileGrid {
+    rows: Vec<Vec<TileKind>>,
+}
+
+impl TileGrid {
+    pub fn get(&self, tile: IVec2) -> TileKind {
+        if tile.x < 0 || tile.y < 0 {
+            return TileKind::Ground;
+        }
+        self.rows
+            .get(tile.y as usize)
+            .and_then(|row| row.get(tile.x as usize))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Every tile that isn't ground.
+    pub fn iter(&self) -> impl Iterator<Item = (IVec2, TileKind)> + '_ {
+        self.rows.iter().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .filter(|(_, kind)| **kind != TileKind::Ground)
+                .map(move |(x, kind)| (IVec2::new(x as i32, y as i32), *kind))
+        })
+    }
+}
+
+impl TryFrom<Vec<String>> for TileGrid {
+    type Error = String;
+
+    fn try_from(rows: Vec<String>) -> Result<Self, Self::Error> {
+        let rows = rows
+            .iter()
+            .enumerate()
+            .map(|(y, row)| {
+                row.chars()
+                    .enumerate()
+                    .map(|(x, ch)| {
+                        TileKind::from_char(ch)
+                            .ok_or_else(|| format!("unexpected '{ch}' at tile ({x}, {y})"))
+                    })
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(TileGrid { rows })
+    }
+}
+
+/// An authored bird placement in a level file.
+#[derive(Clone, Debug, Reflect, serde::Deserialize)]
+pub struct BirdSpawn {
+    /// Board tile of the bird, `x` across and `y` along the board (world Z)
+    pub pos: IVec2,
+    #[serde(default)]
+    pub kind: BirdKind,
+    /// Optional name shown in the inspector and logs
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub behavior: BirdBehavior,
+}
+
+/// How a bird moves around the board, a tile at most every turn. Birds fly over the
+/// logs, but never land on a rock or another bird.
+#[derive(Clone, Debug, Default, PartialEq, Reflect, serde::Deserialize)]
+pub enum BirdBehavior {
+    #[default]
+    Still,
+    /// Hops a tile every `every` turns, taking the `moves` one after the other
+    Hop { every: u32, moves: Vec<Direction> },
+    /// Hops away from the player when they end a turn `range` tiles away or closer
+    Flee { range: u32 },
+    /// Flies to the next tile of `path` every turn, and back to its own tile after the
+    /// last one. Each tile is next to the one before it.
+    Circle { path: Vec<IVec2> },
+}
+
+impl BirdBehavior {
+    /// Turns before the behavior repeats on its own, a bird that is held up by another
+    /// one can fall behind it.
+    pub fn period(&self) -> u32 {
+        match self {
+            BirdBehavior::Hop { every, .. } => (*every).max(1),
+            _ => 1,
+        }
+    }
+}
+
+#[derive(Component, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect, serde::Deserialize)]
+pub enum BirdKind {
+    #[default]
+    Common,
+    Golden,
+}
+
+impl BirdKind {
+    pub fn scale(&self) -> f32 {
+        match self {
+            BirdKind::Common => 0.2,
+            BirdKind::Golden => 0.28,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Reflect, serde::Deserialize)]
+#[serde(try_from = "LogSequenceData")]
+pub struct LogSequence {
+    /// The spawn pattern, it repeats every `steps.len()` turns
+    pub steps: Vec<SpawnStep>,
+    /// How many steps should the log sequence spawn for
+    log_sequence_step_length: u32,
+    /// Position of the spawned logs along `edge`, the step offsets are added to it
+    pub spawn_offset_x_base: i32,
+    /// Board edge the logs spawn at, they roll away from it across the board
+    pub edge: SpawnEdge,
+    /// Tiles rolled per turn
+    pub speed: u32,
+    /// Tiles covered by each log
+    pub length: u32,
+    /// The pattern step that last fired, its offsets are used while the burst lasts
+    pub sequence_index: usize,
+    /// Turns left in the current burst
+    pub sequence_step_counter: u32,
+}
+
+impl Default for LogSequence {
+    fn default() -> Self {
+        Self {
+            steps: Vec::new(),
+            log_sequence_step_length: 0,
+            spawn_offset_x_base: 0,
+            edge: SpawnEdge::default(),
+            speed: LOG_ROLL_TILES as u32,
+            length: LOG_LENGTH as u32,
+            sequence_index: 0,
+            sequence_step_counter: 0,
+        }
+    }
+}
+
+/// The board edge a [`LogSequence`] spawns its logs at. North is the far end of the
+/// board from the player's start, East is `+X`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Reflect, serde::Deserialize)]
+pub enum SpawnEdge {
+    #[default]
+    North,
+    South,
+    East,
+    West,
+}
+
+impl SpawnEdge {
+    /// The tile a log rolls by each time it moves, away from the edge.
+    pub fn roll_direction(&self) -> IVec2 {
+        match self {
+            SpawnEdge::North => IVec2::NEG_Y,
+            SpawnEdge::South => IVec2::Y,
+            SpawnEdge::East => IVec2::NEG_X,
+            SpawnEdge::West => IVec2::X,
+        }
+    }
+
+    /// Number of tiles along the edge.
+    pub fn len(&self, board_size: IVec2) -> i32 {
+        match self {
+            SpawnEdge::North | SpawnEdge::South => board_size.x,
+            SpawnEdge::East | SpawnEdge::West => board_size.y,
+        }
+    }
+
+    /// The tile `along` the edge and `inward` from it. An `inward` of 0 is just off the
+    /// board, the log rolls onto it on its first turn.
+    pub fn spawn_tile(&self, board_size: IVec2, along: i32, inward: i32) -> IVec2 {
+        match self {
+            SpawnEdge::North => IVec2::new(along, board_size.y - inward),
+            SpawnEdge::South => IVec2::new(along, inward - 1),
+            SpawnEdge::East => IVec2::new(board_size.x - inward, along),
+            SpawnEdge::West => IVec2::new(inward - 1, along),
+        }
+    }
+}
+
+/// Where a [`LogSequence`] is in its pattern. Kept apart from the sequence so the
+/// simulation can copy it around with the rest of the board.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SequenceState {
+    /// The pattern step that last fired
+    pub index: usize,
+    /// Turns left in the current burst
+    pub counter: u32,
+}
+
+/// One turn of a [`LogSequence`] pattern.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Reflect, serde::Deserialize)]
+#[serde(default)]
+pub struct SpawnStep {
+    /// Any value above 0 spawns a log, 0 leaves a gap in the pattern
+    pub nth_step: u32,
+    pub offset_x: i32,
+    pub offset_z: i32,
+}
+
+impl SpawnStep {
+    pub fn spawns(&self) -> bool {
+        self.nth_step > 0
+    }
+}
+
+/// How a [`LogSequence`] is written in a level file. The pattern is either a `pattern`
+/// string (see [`crate::spawn_pattern`]), a list of `steps`, or the parallel
+/// `log_sequence_nth_step`/`spawn_offset_x`/`spawn_offset_z` arrays of the original
+/// format, which can be any length as long as they match.
+#[derive(serde::Deserialize)]
+struct LogSequenceData {
+    #[serde(default)]
+    pattern: String,
+    #[serde(default)]
+    steps: Vec<SpawnStep>,
+    #[serde(default, deserialize_with = "seq_or_tuple")]
+    log_sequence_nth_step: Vec<u32>,
+    #[serde(default, deserialize_with = "seq_or_tuple")]
+    spawn_offset_x: Vec<i32>,
+    #[serde(default, deserialize_with = "seq_or_tuple")]
+    spawn_offset_z: Vec<i32>,
+    #[serde(default)]
+    log_sequence_step_length: u32,
+    #[serde(default)]
+    spawn_offset_x_base: i32,
+    #[serde(default)]
+    edge: SpawnEdge,
+    #[serde(default = "default_speed")]
+    speed: u32,
+    #[serde(default = "default_length")]
+    length: u32,
+}
+
+fn default_speed() -> u32 {
+    LOG_ROLL_TILES as u32
+}
+
+fn default_length() -> u32 {
+    LOG_LENGTH as u32
+}
+
+impl TryFrom<LogSequenceData> for LogSequence {
+    type Error = String;
+
+    fn try_from(data: LogSequenceData) -> Result<Self, Self::Error> {
+        let forms = [
+            !data.pattern.is_empty(),
+            !data.steps.is_empty(),
+            !data.log_sequence_nth_step.is_empty(),
+        ];
+        if forms.iter().filter(|given| **given).count() > 1 {
+            return Err("use only one of pattern, steps or log_sequence_nth_step".to_string());
+        }
+
+        let steps = if !data.pattern.is_empty() {
+            parse_pattern(&data.pattern)
+                .map_err(|e| format!("pattern \"{}\": {e}", data.pattern))?
+        } else if data.log_sequence_nth_step.is_empty() {
+            if !data.spawn_offset_x.is_empty() || !data.spawn_offset_z.is_empty() {
+                return Err("spawn offsets given without log_sequence_nth_step".to_string());
+            }
+            data.steps
+        } else {
+            let len = data.log_sequence_nth_step.len();
+            for (name, offsets) in [
+                ("spawn_offset_x", &data.spawn_offset_x),
+                ("spawn_offset_z", &data.spawn_offset_z),
+            ] {
+                if !offsets.is_empty() && offsets.len() != len {
+                    return Err(format!(
+                        "{name} has {} entries but log_sequence_nth_step has {len}",
+                        offsets.len()
+                    ));
+                }
+            }
+            (0..len)
+                .map(|i| SpawnStep {
+                    nth_step: data.log_sequence_nth_step[i],
+                    offset_x: data.spawn_offset_x.get(i).copied().unwrap_or(0),
+                    offset_z: data.spawn_offset_z.get(i).copied().unwrap_or(0),
+                })
+                .collect()
+        };
+        if steps.is_empty() {
+            return Err("log sequence has no steps".to_string());
+        }
+
+        Ok(LogSequence {
+            steps,
+            log_sequence_step_length: data.log_sequence_step_length,
+            spawn_offset_x_base: data.spawn_offset_x_base,
+            edge: data.edge,
+            speed: data.speed,
+            length: data.length,
+            ..default()
+        })
+    }
+}
+
+/// Reads a list written either as `[1, 0, 1]` or as the fixed size array form `(1, 0, 1)`.
+fn seq_or_tuple<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: serde::Deserialize<'de>,
+{
+    struct SeqVisitor<T>(std::marker::PhantomData<T>);
+
+    impl<'de, T: serde::Deserialize<'de>> serde::de::Visitor<'de> for SeqVisitor<T> {
+        type Value = Vec<T>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a list of values")
+        }
+
+        fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<Vec<T>, A::Error> {
+            let mut values = Vec::new();
+            while let Some(value) = seq.next_element()? {
+                values.push(value);
+            }
+            Ok(values)
+        }
+    }
+
+    deserializer.deserialize_any(SeqVisitor(std::marker::PhantomData))
+}
+
+impl LogSequence {
+    pub fn new(steps: Vec<SpawnStep>, step_length: u32, spawn_offset_x_base: i32) -> Self {
+        Self {
+            steps,
+            log_sequence_step_length: step_length,
+            spawn_offset_x_base,
+            ..default()
+        }
+    }
+
+    /// Builds a sequence from the text pattern syntax of [`crate::spawn_pattern`].
+    pub fn from_pattern(
+        pattern: &str,
+        step_length: u32,
+        spawn_offset_x_base: i32,
+    ) -> Result<Self, crate::spawn_pattern::PatternError> {
+        Ok(Self::new(
+            parse_pattern(pattern)?,
+            step_length,
+            spawn_offset_x_base,
+        ))
+    }
+
+    /// The steps written in the text pattern syntax, e.g. for the level editor.
+    pub fn pattern(&self) -> String {
+        format_pattern(&self.steps)
+    }
+
+    /// Number of turns before the pattern repeats.
+    pub fn period(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub fn seq_idx(&self, step: u32) -> usize {
+        step as usize % self.steps.len()
+    }
+
+    /// Moves `state` on to turn `step`, returns `true` if the sequence spawns a log this turn.
+    ///
+    /// Once a pattern step fires the sequence keeps spawning from that step for
+    /// `log_sequence_step_length` consecutive turns, the pattern is ignored until the
+    /// burst is over.
+    pub fn advance_state(&self, state: &mut SequenceState, step: u32) -> bool {
+        if state.counter > 0 {
+            state.counter -= 1;
+            return true;
+        }
+        let seq_idx = self.seq_idx(step);
+        if self.steps[seq_idx].spawns() {
+            state.index = seq_idx;
+            // This turn is the first of the burst
+            state.counter = self.log_sequence_step_length.saturating_sub(1);
+            true
+        } else {
+            false
+        }
+    }
+
+    /// [`LogSequence::advance_state`] on the sequence's own `sequence_index` and counter.
+    pub fn advance(&mut self, step: u32) -> bool {
+        let mut state = self.state();
+        let spawns = self.advance_state(&mut state, step);
+        self.set_state(state);
+        spawns
+    }
+
+    pub fn state(&self) -> SequenceState {
+        SequenceState {
+            index: self.sequence_index,
+            counter: self.sequence_step_counter,
+        }
+    }
+
+    pub fn set_state(&mut self, state: SequenceState) {
+        self.sequence_index = state.index;
+        self.sequence_step_counter = state.counter;
+    }
+
+    /// Spawn column and row offset of the pattern step at `index`.
+    pub fn spawn_offset(&self, index: usize) -> IVec2 {
+        let step = self.steps[index];
+        IVec2::new(self.spawn_offset_x_base + step.offset_x, step.offset_z)
+    }
+
+    pub fn get_nth_step(&self) -> u32 {
+        self.steps[self.sequence_index].nth_step
+    }
+
+    pub fn get_step_length(&self) -> u32 {
+        self.log_sequence_step_length
+    }
+
+    pub fn get_spawn_offset_x(&self) -> i32 {
+        self.spawn_offset(self.sequence_index).x
+    }
+
+    pub fn get_spawn_offset_z(&self) -> i32 {
+        self.spawn_offset(self.sequence_index).y
+    }
+
+    pub fn debug_print(&self) {
+        let nth_steps: Vec<u32> = self.steps.iter().map(|step| step.nth_step).collect();
+        let offsets_x: Vec<i32> = self.steps.iter().map(|step| step.offset_x).collect();
+        let offsets_z: Vec<i32> = self.steps.iter().map(|step| step.offset_z).collect();
+        info!(">>>> Spawning log");
+        info!(
+            "\nseq:         [{}]\nseq offx:    [{}]\nseq offz:    [{}]\nseq_idx:     [{}]\nseq_nth:     [{}]\noffsetx:     [{}]\noffsetz:     [{}]",
+            format_array_with_bracket(&nth_steps, self.sequence_index),
+            format_array_with_bracket(&offsets_x, self.sequence_index),
+            format_array_with_bracket(&offsets_z, self.sequence_index),
+            self.sequence_index,
+            self.get_nth_step(),
+            self.get_spawn_offset_x(),
+            self.get_spawn_offset_z()
+        );
+        info!("<<<< Spawning log");
+    }
+}
+
+fn format_array_with_bracket<T: std::fmt::Display>(arr: &[T], index: usize) -> String {
+    let mut parts = Vec::new();
+    for (i, value) in arr.iter().enumerate() {
+        if i == index {
+            parts.push(format!("[{}]", value));
+        } else {
+            parts.push(format!("{}", value));
+        }
+    }
+    parts.join(", ")
+}
+
+#[derive(Component)]
+pub struct LevelErrorText;
+
+/// Fills `Game.levels` from the `.level.ron` assets once loading has finished. The
+/// levels are solved in the background, see [`finish_level_checks`].
+pub fn load_levels(
+    level_assets: Res<LevelAssets>,
+    levels: Res<Assets<Level>>,
+    mut game: ResMut<Game>,
+    mut error_text: Query<(&mut Text, &mut Visibility), With<LevelErrorText>>,
+    mut commands: Commands,
+) {
+    game.levels = level_assets.clone_levels(&levels);
+    if game.levels.is_empty() {
+        error!("No levels were loaded, check levels/campaign.assets.ron");
+    } else {
+        info!("Loaded {} levels", game.levels.len());
+    }
+
+    let mut errors = Vec::new();
+    for (idx, level) in game.levels.iter().enumerate() {
+        let level_errors = validate_level(&game, idx, level);
+        if level_errors.is_empty() {
+            commands.spawn(LevelCheck::new(&game, idx, level, false));
+        }
+        errors.extend(level_errors);
+    }
+    show_level_errors(&mut error_text, &errors);
+}
+
+/// The bird tiles of the current level before its file was edited, set until the board
+/// is rebuilt from the new version.
+#[derive(Resource, Default)]
+pub struct LevelReload(pub Option<Vec<IVec2>>);
+
+/// Re-checks a campaign level when its file changes while the game runs. A level with
+/// errors is reported and the previous version is kept, one without is played once the
+/// solver has beaten it.
+pub fn hot_reload_levels(
+    mut events: EventReader<AssetEvent<Level>>,
+    level_assets: Res<LevelAssets>,
+    levels: Res<Assets<Level>>,
+    game: Res<Game>,
+    checks: Query<(Entity, &LevelCheck)>,
+    mut error_text: Query<(&mut Text, &mut Visibility), With<LevelErrorText>>,
+    mut commands: Commands,
+) {
+    for event in events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+        let Some(idx) = level_assets
+            .levels
+            .iter()
+            .position(|handle| handle.id() == *id)
+        else {
+            continue;
+        };
+        let Some(level) = levels.get(*id) else {
+            continue;
+        };
+        if idx >= game.levels.len() {
+            continue;
+        }
+
+        let errors = validate_level(&game, idx, level);
+        show_level_errors(&mut error_text, &errors);
+        if !errors.is_empty() {
+            warn!("level {}: keeping the previous version", idx + 1);
+            continue;
+        }
+        // An earlier edit that is still being solved is out of date
+        for (entity, check) in checks.iter() {
+            if check.idx == idx && check.reload.is_some() {
+                commands.entity(entity).despawn();
+            }
+        }
+        commands.spawn(LevelCheck::new(&game, idx, level, true));
+    }
+}
+
+/// A level being solved on the [`AsyncComputeTaskPool`], the search can take longer than
+/// a frame.
+#[derive(Component)]
+pub struct LevelCheck {
+    idx: usize,
+    /// The edited version of the level, it replaces the current one once it is beaten
+    reload: Option<Level>,
+    task: Task<Option<Solution>>,
+}
+
+impl LevelCheck {
+    fn new(game: &Game, idx: usize, level: &Level, reload: bool) -> Self {
+        let board = Game {
+            board_size_x: game.board_size_x,
+            board_size_y: game.board_size_y,
+            start: game.start,
+            lives: game.lives,
+            levels: vec![level.clone()],
+            ..default()
+        };
+        let task =
+            AsyncComputeTaskPool::get().spawn(async move { solve(&Simulation::new(&board)) });
+        Self {
+            idx,
+            reload: reload.then(|| level.clone()),
+            task,
+        }
+    }
+}
+
+/// Reports the levels the solver is done with. An edited level that can be beaten
+/// replaces the one being played, one that can't is reported and the previous version is
+/// kept.
+pub fn finish_level_checks(
+    mut checks: Query<(Entity, &mut LevelCheck)>,
+    mut game: ResMut<Game>,
+    mut reload: ResMut<LevelReload>,
+    mut error_text: Query<(&mut Text, &mut Visibility), With<LevelErrorText>>,
+    mut commands: Commands,
+) {
+    for (entity, mut check) in checks.iter_mut() {
+        let Some(solution) = block_on(future::poll_once(&mut check.task)) else {
+            continue;
+        };
+        commands.entity(entity).despawn();
+        let idx = check.idx;
+        if idx >= game.levels.len() {
+            continue;
+        }
+        let level = check.reload.take();
+        let par = level.as_ref().unwrap_or(&game.levels[idx]).scoring.par;
+        let Some(solution) = solution else {
+            let error = format!("level {}: the birds can't all be rescued", idx + 1);
+            error!("{error}");
+            add_level_error(&mut error_text, error);
+            if level.is_some() {
+                warn!("level {}: keeping the previous version", idx + 1);
+            }
+            continue;
+        };
+        info!(
+            "level {}: can be beaten in {} turns: {:?}",
+            idx + 1,
+            solution.turns(),
+            solution.actions
+        );
+        if let Some(par) = par.filter(|par| (*par as usize) < solution.turns()) {
+            warn!("level {}: par of {par} turns can't be reached", idx + 1);
+        }
+
+        let Some(level) = level else {
+            continue;
+        };
+        info!("level {}: reloaded", idx + 1);
+        if idx == game.current_level_index() && reload.0.is_none() {
+            reload.0 = Some(
+                game.current_level()
+                    .birds
+                    .iter()
+                    .map(|bird| bird.pos)
+                    .collect(),
+            );
+        }
+        let bird_map = std::mem::take(&mut game.levels[idx].bird_map);
+        game.levels[idx] = Level { bird_map, ..level };
+    }
+}
+
+/// Logs the diagnostics of a level and returns its errors.
+fn validate_level(game: &Game, idx: usize, level: &Level) -> Vec<String> {
+    let mut errors = Vec::new();
+    for diagnostic in level.validate(game) {
+        if diagnostic.is_error() {
+            error!("level {}: {}", idx + 1, diagnostic);
+            errors.push(format!("level {}: {}", idx + 1, diagnostic));
+        } else {
+            warn!("level {}: {}", idx + 1, diagnostic);
+        }
+    }
+    errors
+}
+
+/// Adds an error to the ones on screen.
+fn add_level_error(
+    error_text: &mut Query<(&mut Text, &mut Visibility), With<LevelErrorText>>,
+    error: String,
+) {
+    for (mut text, mut vis) in error_text.iter_mut() {
+        if !text.is_empty() {
+            text.push('\n');
+        }
+        text.push_str(&error);
+        *vis = Visibility::Visible;
+    }
+}
+
+fn show_level_errors(
+    error_text: &mut Query<(&mut Text, &mut Visibility), With<LevelErrorText>>,
+    errors: &[String],
+) {
+    for (mut text, mut vis) in error_text.iter_mut() {
+        **text = errors.join("\n");
+        *vis = if errors.is_empty() {
+            Visibility::Hidden
+        } else {
+            Visibility::Visible
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sequence(nth_step: &[u32], offset_x: &[i32]) -> LogSequence {
+        let steps = nth_step
+            .iter()
+            .zip(offset_x)
+            .map(|(&nth_step, &offset_x)| SpawnStep {
+                nth_step,
+                offset_x,
+                offset_z: 0,
+            })
+            .collect();
+        LogSequence::new(steps, 2, 2)
+    }
+
+    fn bird(x: i32, y: i32) -> BirdSpawn {
+        BirdSpawn {
+            pos: IVec2::new(x, y),
+            kind: BirdKind::Common,
+            name: None,
+            behavior: BirdBehavior::Still,
+        }
+    }
+
+    fn level(seq: Vec<LogSequence>, birds: Vec<BirdSpawn>) -> Level {
+        Level {
+            seq,
+            birds,
+            ..default()
+        }
+    }
+
+    #[test]
+    fn test_valid_level_has_no_diagnostics() {
+        // The steps with an nth step of 0 are gaps in the pattern
+        let level = level(
+            vec![sequence(&[1, 0, 1, 1, 1, 0, 0, 0], &[0; 8])],
+            vec![bird(7, 2), bird(3, 5)],
+        );
+        assert!(level.validate(&Game::default()).is_empty());
+    }
+
+    #[test]
+    fn test_spawn_column_out_of_bounds() {
+        // base 2 + -3 = -1 spawns, base 2 + 10 = 12 is skipped because step 3 doesn't spawn
+        let level = level(vec![sequence(&[1, 1, 1, 0, 1], &[0, -3, 0, 10, 0])], vec![]);
+        assert_eq!(
+            level.validate(&Game::default()),
+            vec![LevelDiagnostic::SpawnColumnOutOfBounds {
+                seq: 0,
+                step: 1,
+                column: -1
+            }]
+        );
+    }
+
+    #[test]
+    fn test_bird_diagnostics() {
+        let level = level(
+            vec![],
+            vec![bird(7, 2), bird(12, 2), bird(7, 2), bird(0, -1)],
+        );
+        let diagnostics = level.validate(&Game::default());
+        assert_eq!(
+            diagnostics,
+            vec![
+                LevelDiagnostic::BirdOffBoard {
+                    pos: IVec2::new(12, 2)
+                },
+                LevelDiagnostic::DuplicateBird {
+                    pos: IVec2::new(7, 2)
+                },
+                LevelDiagnostic::BirdOffBoard {
+                    pos: IVec2::new(0, -1)
+                },
+            ]
+        );
+        assert!(diagnostics.iter().all(LevelDiagnostic::is_error));
+    }
+
+    #[test]
+    fn test_bird_behavior_diagnostics() {
+        let birds: Vec<BirdSpawn> = ron::from_str(
+            r#"[
+                (pos: (2, 2), behavior: Hop(every: 0, moves: [North])),
+                (pos: (3, 3), behavior: Circle(path: [(3, 4), (4, 4), (4, 3)])),
+                (pos: (4, 3), behavior: Flee(range: 2)),
+                (pos: (0, 11), behavior: Circle(path: [(0, 12)])),
+                (pos: (8, 8), behavior: Circle(path: [(8, 9), (9, 10)])),
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(birds[2].behavior, BirdBehavior::Flee { range: 2 });
+        let mut level = level(vec![], birds);
+        level.rocks = vec![IVec2::new(4, 4)];
+        assert_eq!(
+            level.validate(&Game::default()),
+            vec![
+                LevelDiagnostic::BirdNeverHops {
+                    pos: IVec2::new(2, 2)
+                },
+                LevelDiagnostic::BirdPathBlocked {
+                    pos: IVec2::new(3, 3),
+                    tile: IVec2::new(4, 4)
+                },
+                LevelDiagnostic::BirdPathBlocked {
+                    pos: IVec2::new(0, 11),
+                    tile: IVec2::new(0, 12)
+                },
+                LevelDiagnostic::BirdPathGap {
+                    pos: IVec2::new(8, 8),
+                    from: IVec2::new(8, 9),
+                    to: IVec2::new(9, 10)
+                },
+                LevelDiagnostic::BirdPathGap {
+                    pos: IVec2::new(8, 8),
+                    from: IVec2::new(9, 10),
+                    to: IVec2::new(8, 8)
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rock_diagnostics() {
+        // Rocks wall off the corner at (0, 11), and the tiles a jump into it starts from
+        let mut level = level(vec![], vec![bird(0, 11)]);
+        level.rocks = vec![
+            IVec2::new(0, 10),
+            IVec2::new(1, 11),
+            IVec2::new(0, 9),
+            IVec2::new(2, 11),
+            IVec2::new(3, 12),
+        ];
+        assert_eq!(
+            level.validate(&Game::default()),
+            vec![
+                LevelDiagnostic::RockOffBoard {
+                    pos: IVec2::new(3, 12)
+                },
+                LevelDiagnostic::BirdUnreachable {
+                    pos: IVec2::new(0, 11)
+                },
+            ]
+        );
+
+        level.rocks = vec![Game::default().start];
+        assert!(
+            level
+                .validate(&Game::default())
+                .contains(&LevelDiagnostic::RockOnStart {
+                    pos: IVec2::new(6, 0)
+                })
+        );
+    }
+
+    #[test]
+    fn test_nest_diagnostics() {
+        let mut level = level(vec![], vec![bird(3, 3)]);
+        level.nest = Some(IVec2::new(5, 9));
+        assert!(level.validate(&Game::default()).is_empty());
+
+        // Walled off in the corner, out of jumping range too
+        level.nest = Some(IVec2::new(0, 11));
+        level.rocks = vec![
+            IVec2::new(0, 10),
+            IVec2::new(1, 11),
+            IVec2::new(0, 9),
+            IVec2::new(2, 11),
+        ];
+        let diagnostics = level.validate(&Game::default());
+        assert_eq!(
+            diagnostics,
+            vec![LevelDiagnostic::NestUnreachable {
+                pos: IVec2::new(0, 11)
+            }]
+        );
+        assert!(diagnostics[0].is_error());
+    }
+
+    #[test]
+    fn test_tile_diagnostics() {
+        let mut level = level(vec![], vec![bird(3, 3)]);
+        level.tiles = ron::from_str(r#"["......~", "", "...~"]"#).unwrap();
+        assert_eq!(level.tiles.get(IVec2::new(3, 2)), TileKind::Water);
+        assert_eq!(level.tiles.get(IVec2::new(3, 1)), TileKind::Ground);
+        assert_eq!(level.tiles.iter().count(), 2);
+        level.nest = Some(IVec2::new(3, 2));
+        let diagnostics = level.validate(&Game::default());
+        assert_eq!(
+            diagnostics,
+            vec![
+                LevelDiagnostic::WaterOnStart {
+                    pos: IVec2::new(6, 0)
+                },
+                // Nothing can be reached from the water
+                LevelDiagnostic::BirdUnreachable {
+                    pos: IVec2::new(3, 3)
+                },
+                LevelDiagnostic::NestUnreachable {
+                    pos: IVec2::new(3, 2)
+                },
+            ]
+        );
+        assert!(diagnostics.iter().all(LevelDiagnostic::is_error));
+
+        // Water cuts off the corner like rocks would
+        level.birds = vec![bird(0, 11)];
+        level.nest = None;
+        let mut rows = vec![String::new(); 12];
+        rows[9] = "~".into();
+        rows[10] = "~".into();
+        rows[11] = ".~~".into();
+        level.tiles = rows.try_into().unwrap();
+        assert_eq!(
+            level.validate(&Game::default()),
+            vec![LevelDiagnostic::BirdUnreachable {
+                pos: IVec2::new(0, 11)
+            }]
+        );
+
+        assert!(ron::from_str::<TileGrid>(r#"["..x"]"#).is_err());
+        let conveyors: TileGrid = ron::from_str(r#"["NESW"]"#).unwrap();
+        assert_eq!(
+            conveyors.get(IVec2::new(3, 0)),
+            TileKind::Conveyor(Direction::West)
+        );
+    }
+
+    #[test]
+    fn test_stars_out_of_order_is_a_warning() {
+        let mut level = level(vec![], vec![]);
+        level.scoring.stars = [300, 200, 600];
+        let diagnostics = level.validate(&Game::default());
+        assert_eq!(
+            diagnostics,
+            vec![LevelDiagnostic::StarsOutOfOrder {
+                stars: [300, 200, 600]
+            }]
+        );
+        assert!(!diagnostics[0].is_error());
+    }
+
+    #[test]
+    fn test_empty_sequence() {
+        let level = level(vec![sequence(&[], &[])], vec![]);
+        assert_eq!(
+            level.validate(&Game::default()),
+            vec![LevelDiagnostic::EmptySequence { seq: 0 }]
+        );
+    }
+
+    #[test]
+    fn test_sequence_period() {
+        let mut seq = sequence(&[1, 0, 1], &[0, 0, 2]);
+        assert_eq!(seq.period(), 3);
+        assert_eq!(seq.seq_idx(4), 1);
+        seq.sequence_index = seq.seq_idx(5);
+        assert_eq!(seq.get_spawn_offset_x(), 4);
+        assert_eq!(seq.get_nth_step(), 1);
+    }
+
+    /// The turns out of the first `turns` that spawn a log.
+    fn spawn_turns(seq: &mut LogSequence, turns: u32) -> Vec<u32> {
+        (0..turns).filter(|step| seq.advance(*step)).collect()
+    }
+
+    #[test]
+    fn test_burst_length_one_follows_pattern() {
+        let mut seq = LogSequence::from_pattern("X.XX ....", 1, 0).unwrap();
+        assert_eq!(spawn_turns(&mut seq, 16), vec![0, 2, 3, 8, 10, 11]);
+        // 0 behaves like 1, every firing spawns once
+        let mut seq = LogSequence::from_pattern("X.XX ....", 0, 0).unwrap();
+        assert_eq!(spawn_turns(&mut seq, 16), vec![0, 2, 3, 8, 10, 11]);
+    }
+
+    #[test]
+    fn test_burst_spawns_consecutive_turns() {
+        let mut seq = LogSequence::from_pattern("X... ....", 3, 0).unwrap();
+        assert_eq!(spawn_turns(&mut seq, 16), vec![0, 1, 2, 8, 9, 10]);
+    }
+
+    #[test]
+    fn test_burst_ignores_pattern_until_done() {
+        // The X at step 2 falls inside the burst from step 0, step 4 starts a new one
+        let mut seq = LogSequence::from_pattern("X.X. X...", 3, 0).unwrap();
+        assert_eq!(
+            spawn_turns(&mut seq, 16),
+            vec![0, 1, 2, 4, 5, 6, 8, 9, 10, 12, 13, 14]
+        );
+    }
+
+    #[test]
+    fn test_burst_keeps_firing_offsets() {
+        let mut seq = LogSequence::from_pattern("X+1^1 . X+3 .", 2, 2).unwrap();
+        let offsets: Vec<Option<(i32, i32)>> = (0..4)
+            .map(|step| {
+                seq.advance(step)
+                    .then(|| (seq.get_spawn_offset_x(), seq.get_spawn_offset_z()))
+            })
+            .collect();
+        assert_eq!(
+            offsets,
+            vec![Some((3, 1)), Some((3, 1)), Some((5, 0)), Some((5, 0))]
+        );
+    }
+
+    #[test]
+    fn test_deserialize_legacy_arrays() {
+        let seq: LogSequence = ron::from_str(
+            "(
+                log_sequence_step_length: 2,
+                log_sequence_nth_step:  (1, 0, 1, 1, 1, 0, 0, 0),
+                spawn_offset_z:         (0, 1, 0, 0, 0, 0, 0, 0),
+                spawn_offset_x:         (0, 1, 0, 0, 1, 0, 0, 0),
+                spawn_offset_x_base: 2,
+            )",
+        )
+        .unwrap();
+        assert_eq!(seq.period(), 8);
+        assert_eq!(seq.get_step_length(), 2);
+        assert_eq!(
+            seq.steps[1],
+            SpawnStep {
+                nth_step: 0,
+                offset_x: 1,
+                offset_z: 1
+            }
+        );
+    }
+
+    #[test]
+    fn test_deserialize_variable_length() {
+        let seq: LogSequence = ron::from_str(
+            "(
+                log_sequence_nth_step: [1, 0, 1],
+                spawn_offset_x: [0, 0, 3],
+                spawn_offset_x_base: 6,
+            )",
+        )
+        .unwrap();
+        assert_eq!(seq.period(), 3);
+        assert_eq!(seq.steps[2].offset_x, 3);
+        assert_eq!(seq.steps[2].offset_z, 0);
+
+        let seq: LogSequence = ron::from_str(
+            "(steps: [(nth_step: 1), (), (nth_step: 2, offset_z: 1)], spawn_offset_x_base: 6)",
+        )
+        .unwrap();
+        assert_eq!(seq.period(), 3);
+        assert!(!seq.steps[1].spawns());
+        assert_eq!(seq.steps[2].offset_z, 1);
+    }
+
+    #[test]
+    fn test_deserialize_pattern() {
+        let seq: LogSequence = ron::from_str(
+            r#"(pattern: "X.+1^1XX X+1...", log_sequence_step_length: 2, spawn_offset_x_base: 2)"#,
+        )
+        .unwrap();
+        assert_eq!(seq.period(), 8);
+        assert_eq!(
+            seq.steps[1],
+            SpawnStep {
+                nth_step: 0,
+                offset_x: 1,
+                offset_z: 1
+            }
+        );
+        assert_eq!(seq.pattern(), "X.+1^1XX X+1...");
+
+        let err = ron::from_str::<LogSequence>(r#"(pattern: "X.Q")"#).unwrap_err();
+        assert!(
+            err.to_string().contains("column 3: unexpected 'Q'"),
+            "{err}"
+        );
+        let both = ron::from_str::<LogSequence>(r#"(pattern: "X", steps: [(nth_step: 1)])"#);
+        assert!(both.is_err());
+    }
+
+    #[test]
+    fn test_deserialize_movement() {
+        let seq: LogSequence =
+            ron::from_str(r#"(pattern: "X", edge: West, speed: 1, length: 3)"#).unwrap();
+        assert_eq!(seq.edge, SpawnEdge::West);
+        assert_eq!((seq.speed, seq.length), (1, 3));
+
+        let seq: LogSequence = ron::from_str(r#"(pattern: "X")"#).unwrap();
+        assert_eq!(seq.edge, SpawnEdge::North);
+        assert_eq!((seq.speed, seq.length), (2, 4));
+    }
+
+    #[test]
+    fn test_side_edge_diagnostics() {
+        let mut seq = sequence(&[1, 1], &[0, 9]);
+        seq.edge = SpawnEdge::East;
+        seq.speed = 0;
+        let game = Game {
+            board_size_x: 14,
+            board_size_y: 10,
+            ..default()
+        };
+        // Along the East edge the board is only 10 tiles long
+        assert_eq!(
+            level(vec![seq], vec![]).validate(&game),
+            vec![
+                LevelDiagnostic::ZeroSpeed { seq: 0 },
+                LevelDiagnostic::SpawnColumnOutOfBounds {
+                    seq: 0,
+                    step: 1,
+                    column: 11
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_spawn_tile() {
+        let size = IVec2::new(12, 10);
+        assert_eq!(SpawnEdge::North.spawn_tile(size, 3, 0), IVec2::new(3, 10));
+        assert_eq!(SpawnEdge::South.spawn_tile(size, 3, 1), IVec2::new(3, 0));
+        assert_eq!(SpawnEdge::East.spawn_tile(size, 3, 0), IVec2::new(12, 3));
+        assert_eq!(SpawnEdge::West.spawn_tile(size, 3, 0), IVec2::new(-1, 3));
+    }
+
+    #[test]
+    fn test_deserialize_rejects_bad_sequences() {
+        let misaligned = ron::from_str::<LogSequence>(
+            "(log_sequence_nth_step: [1, 0, 1], spawn_offset_x: [0, 0])",
+        );
+        assert!(misaligned.is_err());
+        let empty = ron::from_str::<LogSequence>("(spawn_offset_x_base: 6)");
+        assert!(empty.is_err());
+    }
+
+    #[test]
+    fn test_reachable_tiles() {
+        // A wall along x == 2 and 3 cuts the 6x4 board in half, too wide to jump
+        let walkable = |pos: IVec2| {
+            pos.x >= 0 && pos.x < 6 && pos.y >= 0 && pos.y < 4 && pos.x != 2 && pos.x != 3
+        };
+        let reachable = reachable_tiles(IVec2::ZERO, walkable);
+        assert_eq!(reachable.len(), 8);
+        assert!(reachable.contains(&IVec2::new(1, 3)));
+        assert!(!reachable.contains(&IVec2::new(4, 0)));
+        assert!(reachable_tiles(IVec2::new(2, 0), walkable).is_empty());
+
+        // A single rock is jumped over
+        let walkable = |pos: IVec2| pos.x >= 0 && pos.x < 3 && pos.y == 0 && pos.x != 1;
+        let reachable = reachable_tiles(IVec2::ZERO, walkable);
+        assert!(reachable.contains(&IVec2::new(2, 0)));
+    }
+}
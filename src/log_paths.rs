@@ -0,0 +1,68 @@
+//! An optional overlay of the tiles the logs roll onto over the next few turns. The
+//! [`Simulation`] works it out, so the overlay shows what the turns will do.
+
+use std::f32::consts::FRAC_PI_2;
+
+use bevy::prelude::*;
+
+use crate::{Difficulty, Game, TILE_SIZE, simulation::Simulation};
+
+/// Turns ahead the overlay looks
+pub const PREDICTED_TURNS: u32 = 3;
+
+#[derive(Resource)]
+pub struct LogPathOverlay {
+    pub enabled: bool,
+    pub turns: u32,
+}
+
+impl Default for LogPathOverlay {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            turns: PREDICTED_TURNS,
+        }
+    }
+}
+
+pub fn toggle_log_paths(
+    input: Res<ButtonInput<KeyCode>>,
+    mut overlay: ResMut<LogPathOverlay>,
+    difficulty: Res<Difficulty>,
+) {
+    if !input.just_pressed(KeyCode::KeyP) {
+        return;
+    }
+    if *difficulty == Difficulty::Hard {
+        info!("No log paths in hard mode");
+        return;
+    }
+    overlay.enabled = !overlay.enabled;
+    info!("Log paths toggled: {}", overlay.enabled);
+}
+
+/// Outlines the tiles the logs roll onto, brighter the sooner they get there.
+pub fn draw_log_paths(
+    mut gizmos: Gizmos,
+    game: Res<Game>,
+    overlay: Res<LogPathOverlay>,
+    difficulty: Res<Difficulty>,
+) {
+    if !overlay.enabled || *difficulty == Difficulty::Hard {
+        return;
+    }
+    let sim = Simulation::new(&game);
+    for sweep in sim.log_paths(&game.board, overlay.turns) {
+        let fade = 1.0 - (sweep.turn - 1) as f32 / overlay.turns as f32;
+        let position = Vec3::new(
+            sweep.tile.x as f32 * TILE_SIZE,
+            0.03,
+            sweep.tile.y as f32 * TILE_SIZE,
+        );
+        gizmos.rect(
+            Isometry3d::new(position, Quat::from_rotation_x(FRAC_PI_2)),
+            Vec2::splat(TILE_SIZE * 0.8),
+            Color::linear_rgba(1.0, 0.8, 0.1, fade),
+        );
+    }
+}
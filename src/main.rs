@@ -1,37 +1,61 @@
 mod audio;
 mod collision_system;
+mod generator;
+mod level;
+mod log_paths;
 mod models;
+mod score;
+mod simulation;
+mod solver;
+mod spawn_pattern;
+mod undo;
 
 use std::f32::consts::{FRAC_PI_2, PI};
 
 use bevy::{
     color::palettes::{
-        css::{GOLD, YELLOW},
+        css::{GOLD, RED, YELLOW},
         tailwind::CYAN_200,
     },
+    ecs::system::SystemParam,
     pbr::NotShadowCaster,
-    platform::collections::HashMap,
     prelude::*,
     render::camera::ScalingMode,
 };
 
-use bevy_asset_loader::loading_state::{
-    LoadingState, LoadingStateAppExt, config::ConfigureLoadingState,
+use bevy_asset_loader::{
+    loading_state::{LoadingState, LoadingStateAppExt, config::ConfigureLoadingState},
+    standard_dynamic_asset::StandardDynamicAssetCollection,
 };
 use bevy_common_assets::ron::RonAssetPlugin;
+use bevy_rand::prelude::{EntropyPlugin, GlobalEntropy, WyRand};
 // use bevy_inspector_egui::prelude::*;
 use collision_system::{
-    CollisionEvent, LLAabb3d, collision_detection_system, player_collision_handling_system,
+    CollisionEvent, LLAabb3d, collision_detection_system, log_rock_collision_handling_system,
+    player_collision_handling_system, shake_rocks,
 };
+use generator::{GeneratedLevel, GeneratedLevels, LevelGeneration, finish_level_generation};
+use level::{
+    BirdKind, BirdSpawn, Level, LevelAssets, LevelErrorText, LevelReload, TileKind,
+    finish_level_checks, hot_reload_levels, load_levels,
+};
+use log_paths::{LogPathOverlay, draw_log_paths, toggle_log_paths};
 use models::ModelAssets;
+use rand::RngCore;
+use score::Score;
+use simulation::{
+    Action, Board, HEAVY_BEVY, JUMP_TILES, LIVES, Outcome, SimBird, SimLog, Simulation, Turn,
+};
+use undo::{UndoHistory, record_turn, undo_turns};
 
 // Constants for animation durations
 const PLAYER_ACTION_ANIMATION_DURATION: f32 = 0.44; // e.g., move or jump start
 const GAME_MOVE_ANIMATION_DURATION: f32 = 0.47; // e.g., environment changes, enemies move
 const PLAYER_JUMP_ANIMATION_DURATION: f32 = 0.39; // e.g., landing animation
 const PLAYER_JUMP_LAND_ANIMATION_DURATION: f32 = 0.42; // e.g., landing animation
-const SEQUENCE_LENGTH: usize = 8;
 const BIRD_Y: i32 = 2;
+/// How much longer a jump takes for every carried bird, the jump is lower by as much
+const BIRD_JUMP_WEIGHT: f32 = 0.2;
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, States)]
 pub enum AppState {
@@ -41,6 +65,7 @@ pub enum AppState {
     InGame,
     EndGame,
     WinGame,
+    CampaignComplete,
 }
 
 #[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Hash, SubStates)]
@@ -65,14 +90,19 @@ enum GameObjectType {
 #[derive(Debug, Event, PartialEq)]
 pub enum GameEvent {
     Over,
-    Win,
+    Win { stars: u32 },
+    CampaignComplete,
 }
 
 impl GameEvent {
     pub fn text(&self) -> String {
         match self {
             GameEvent::Over => "GAME OVER".to_string(),
-            GameEvent::Win => "ALL BIRDS RESCUED".to_string(),
+            GameEvent::Win { stars } => format!("ALL BIRDS RESCUED\nSTARS: {stars}/3"),
+            GameEvent::CampaignComplete => {
+                "CAMPAIGN COMPLETE\nPress Enter to play again\nPress G for a generated level"
+                    .to_string()
+            }
         }
     }
 }
@@ -90,78 +120,21 @@ struct PlayerActionTracker {
 #[derive(Resource, Debug)]
 struct ActionTimer(Timer);
 
-impl Default for ActionTimer {
-    fn default() -> Self {
-        ActionTimer(Timer::from_seconds(1.0, TimerMode::Once))
-    }
-}
-
-#[derive(Clone, Reflect, serde::Deserialize, Asset)]
-pub struct Level {
-    pub seq: Vec<LogSequence>,
-    pub bird_map: HashMap<IVec3, Entity>,
-}
-
-#[derive(Clone, Reflect, Default, serde::Deserialize)]
-pub struct LogSequence {
-    /// This value is used to mod with the step to get the nth step to spawn
-    pub log_sequence_nth_step: [u32; SEQUENCE_LENGTH],
-    /// How many steps should the log sequence spawn for
-    log_sequence_step_length: u32,
-    pub spawn_offset_x: [i32; SEQUENCE_LENGTH],
-    pub spawn_offset_z: [i32; SEQUENCE_LENGTH],
-    pub spawn_offset_x_base: i32,
-    pub sequence_index: usize,
-    pub sequence_step_counter: u32,
-}
-
-impl LogSequence {
-    /// increment the sequence index and wrap if its reached the end
-    // pub fn seq_next(&mut self) {
-    //     // Increment the sequence index and wrap it if it reaches the end.
-    //     // The length of the array `log_sequence_nth_step` is fixed at SEQUENCE_LENGTH (8),
-    //     // which is guaranteed to be non-zero, so the modulo operation is safe.
-    //     self.sequence_index = (self.sequence_index + 1) % self.log_sequence_nth_step.len();
-    // }
-
-    pub fn seq_idx(&self, step: u32) -> usize {
-        step as usize % self.log_sequence_nth_step.len()
-    }
-
-    pub fn check_seq(&self, step: u32, mod_val: u32) -> bool {
-        let idx: usize = self.seq_idx(step);
-        step % self.log_sequence_nth_step[idx] == mod_val
-    }
-
-    pub fn get_nth_step(&self) -> u32 {
-        self.log_sequence_nth_step[self.sequence_index]
-    }
-
-    pub fn get_step_length(&self) -> u32 {
-        self.log_sequence_step_length
-    }
-
-    pub fn get_spawn_offset_x(&self) -> i32 {
-        self.spawn_offset_x_base + self.spawn_offset_x[self.sequence_index]
-    }
+/// The turn the player picked, worked out by the [`Simulation`] up front.
+/// The player and log animations play it back and it is applied to `Game.board` once
+/// the logs have rolled.
+#[derive(Resource, Default)]
+pub struct PendingTurn(pub Option<Turn>);
 
-    pub fn get_spawn_offset_z(&self) -> i32 {
-        self.spawn_offset_z[self.sequence_index]
+impl PendingTurn {
+    pub fn outcome(&self) -> Option<Outcome> {
+        self.0.as_ref().map(|turn| turn.outcome)
     }
+}
 
-    pub fn debug_print(&self) {
-        info!(">>>> Spawning log");
-        info!(
-            "\nseq:         [{}]\nseq offx:    [{}]\nseq offz:    [{}]\nseq_idx:     [{}]\nseq_nth:     [{}]\noffsetx:     [{}]\noffsetz:     [{}]",
-            format_array_with_bracket(&self.log_sequence_nth_step, self.sequence_index),
-            format_array_with_bracket(&self.spawn_offset_x, self.sequence_index),
-            format_array_with_bracket(&self.spawn_offset_z, self.sequence_index),
-            self.sequence_index,
-            self.get_nth_step(),
-            self.get_spawn_offset_x(),
-            self.get_spawn_offset_z()
-        );
-        info!("<<<< Spawning log");
+impl Default for ActionTimer {
+    fn default() -> Self {
+        ActionTimer(Timer::from_seconds(1.0, TimerMode::Once))
     }
 }
 
@@ -172,61 +145,58 @@ pub struct Game {
     pub board_size_x: u32,
     pub board_size_y: u32,
     pub start: IVec2,
-    pub player_pos: IVec3,
-    pub current_step: u32,
     current_level: usize,
     pub levels: Vec<Level>,
+    /// Hits the player can take on the levels that don't set their own `lives`
+    pub lives: u32,
     pub bevy_count: u32,
+    /// Birds rescued before the current level started, restored when the level is lost
+    pub level_start_bevy_count: u32,
+    /// Points of the run. Like the birds and stars, they are kept in memory from one level
+    /// to the next and through restarts of a level, nothing is saved to disk.
+    #[serde(skip)]
+    pub score: Score,
+    /// Best stars earned on each level, 0 for levels not beaten yet
+    pub stars: Vec<u32>,
+    /// Player, logs and birds of the level being played
+    #[reflect(ignore)]
+    #[serde(skip)]
+    pub board: Board,
+}
+
+/// How much help the player gets, `--hard` on the command line picks hard.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Difficulty {
+    #[default]
+    Normal,
+    /// The logs' paths can't be shown
+    Hard,
 }
 
-const LOG1_OFFSET_X: i32 = 2;
-const LOG2_OFFSET_X: i32 = 6;
-const LOG3_OFFSET_X: i32 = 10;
+impl Difficulty {
+    pub fn from_args() -> Self {
+        if std::env::args().any(|arg| arg == "--hard") {
+            Difficulty::Hard
+        } else {
+            Difficulty::Normal
+        }
+    }
+}
 
 impl Default for Game {
     fn default() -> Self {
         Self {
             board_size_x: 12,
             board_size_y: 12,
-            start: IVec2::new(3, 0),
-            current_step: 0,
-            player_pos: IVec3::new(3, 0, 0),
+            start: IVec2::new(6, 0),
             current_level: 0,
+            lives: LIVES,
             bevy_count: 0,
-            #[rustfmt::skip]
-            levels: vec![Level {
-                seq: vec![
-                    LogSequence {
-                        log_sequence_step_length: 2,
-                        log_sequence_nth_step:      [1, 0, 1, 1, 1, 0, 0, 0],
-                        spawn_offset_z:             [0, 1, 0, 0, 0, 0, 0, 0],
-                        spawn_offset_x:             [0, 1, 0, 0, 1, 0, 0, 0],
-                        spawn_offset_x_base: LOG1_OFFSET_X,
-                        ..default()
-                    },
-                    LogSequence {
-                        log_sequence_step_length: 2,
-                        log_sequence_nth_step:      [0, 1, 1, 0, 1, 0, 0, 0],
-                        spawn_offset_z:             [0, 0, 0, 0, 0, 0, 0, 0],
-                        spawn_offset_x:             [0, 1, 0, 0, 1, 0, 0, 0],
-                        spawn_offset_x_base: LOG2_OFFSET_X,
-                        ..default()
-                    },
-                    LogSequence {
-                        log_sequence_step_length: 2,
-                        log_sequence_nth_step:      [1, 0, 1, 1, 0, 1, 0, 1],
-                        spawn_offset_z:             [0, 0, 1, 0, 0, 0, 0, 0],
-                        spawn_offset_x:             [0, 0, 0, 0, 0, 0, 0, 0],
-                        spawn_offset_x_base: LOG3_OFFSET_X,
-                        ..default()
-                    },
-                ],
-                bird_map: HashMap::from_iter([
-                    (IVec3::new(7,BIRD_Y,2), Entity::PLACEHOLDER),
-                    (IVec3::new(8,BIRD_Y,7), Entity::PLACEHOLDER),
-                    (IVec3::new(3,BIRD_Y,5), Entity::PLACEHOLDER),
-                ])
-            }],
+            level_start_bevy_count: 0,
+            score: Score::default(),
+            stars: Vec::new(),
+            levels: Vec::new(),
+            board: Board::default(),
         }
     }
 }
@@ -241,31 +211,39 @@ impl Game {
     }
 
     pub fn is_valid_player_move(&self, dir: &Direction) -> bool {
-        let pos = self.player_pos + dir.value();
-        // Assuming board starts at (0, 0) in the XZ plane
-        if pos.x >= 0
-            && pos.x < self.board_size_x as i32
-            && pos.z >= 0
-            && pos.z < self.board_size_y as i32
-        {
+        if Simulation::new(self).is_valid_action(&self.board, Action::Move(*dir)) {
+            true
+        } else {
+            info!(
+                "outside boundary or blocked: {:?}",
+                self.board.player + dir.value().xz()
+            );
+            false
+        }
+    }
+
+    pub fn is_valid_player_jump(&self, dir: &Direction) -> bool {
+        let sim = Simulation::new(self);
+        let landing = self.board.player + dir.value().xz() * JUMP_TILES;
+        if sim.is_valid_action(&self.board, Action::Jump(*dir)) {
             true
+        } else if !sim.on_board(landing) || sim.is_rock(landing) {
+            info!("can't land on: {landing:?}");
+            false
+        } else if self.board.carried.len() >= HEAVY_BEVY {
+            info!("bevy too heavy to jump: {} birds", self.board.carried.len());
+            false
         } else {
-            println!("outside boundary: {pos:?}");
+            info!("stuck, the player can only wait");
             false
         }
     }
 
-    pub fn is_valid_board_pos(&self, pos: IVec3, dir: &Direction) -> bool {
-        let pos = pos + dir.value();
-        // Assuming board starts at (0, 0) in the XZ plane
-        if pos.x > -1
-            && pos.x <= self.board_size_x as i32
-            && pos.z > -1
-            && pos.z <= self.board_size_y as i32
-        {
+    pub fn is_valid_player_drop(&self) -> bool {
+        if Simulation::new(self).is_valid_action(&self.board, Action::Drop) {
             true
         } else {
-            println!("outside boundary: {pos:?}");
+            info!("no bird to drop on: {:?}", self.board.player);
             false
         }
     }
@@ -277,43 +255,135 @@ impl Game {
     pub fn current_level(&self) -> &Level {
         return &self.levels[self.current_level];
     }
+
+    pub fn current_level_index(&self) -> usize {
+        self.current_level
+    }
+
+    /// Banks the rescued birds and moves on to the next level.
+    /// Returns `false` if the current level was the last one of the campaign.
+    pub fn next_level(&mut self) -> bool {
+        self.level_start_bevy_count = self.bevy_count;
+        self.score.bank();
+        if self.current_level + 1 < self.levels.len() {
+            self.current_level += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Resets the board for the current level, keeping the progress made before it.
+    pub fn restart_level(&mut self, levels: Vec<Level>) {
+        *self = Game {
+            current_level: self.current_level,
+            lives: self.lives,
+            bevy_count: self.level_start_bevy_count,
+            level_start_bevy_count: self.level_start_bevy_count,
+            score: Score::banked(self.score.total),
+            stars: std::mem::take(&mut self.stars),
+            levels,
+            ..default()
+        };
+    }
+
+    /// Adds a level generated from `seed` after the current last one.
+    pub fn add_generated_level(
+        &mut self,
+        generated: &mut GeneratedLevels,
+        seed: u64,
+        level: GeneratedLevel,
+    ) {
+        info!(
+            "Generated a level from seed {seed} after {} attempts, it can be beaten in {} turns",
+            level.attempts,
+            level.solution.turns()
+        );
+        generated.0.push(level.level.clone());
+        self.levels.push(level.level);
+    }
+
+    /// Goes back to the first level with no birds rescued. The stars earned are kept.
+    pub fn restart_campaign(&mut self) {
+        self.current_level = 0;
+        self.bevy_count = 0;
+        self.level_start_bevy_count = 0;
+        self.score = Score::default();
+    }
+
+    /// Keeps `stars` for the current level if they beat its best.
+    pub fn record_stars(&mut self, stars: u32) {
+        let idx = self.current_level;
+        if self.stars.len() <= idx {
+            self.stars.resize(idx + 1, 0);
+        }
+        self.stars[idx] = self.stars[idx].max(stars);
+    }
 }
 
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins, RonAssetPlugin::<Level>::new(&["level.ron"])))
+        .add_plugins((
+            DefaultPlugins,
+            RonAssetPlugin::<Level>::new(&["level.ron"]),
+            EntropyPlugin::<WyRand>::default(),
+        ))
         .init_state::<AppState>()
         .add_sub_state::<GameState>()
         .add_loading_state(
             LoadingState::new(AppState::AssetLoading)
                 .continue_to_state(AppState::InGame)
-                .load_collection::<ModelAssets>(),
+                .with_dynamic_assets_file::<StandardDynamicAssetCollection>(
+                    "levels/campaign.assets.ron",
+                )
+                .load_collection::<ModelAssets>()
+                .load_collection::<LevelAssets>(),
         )
         .init_resource::<PlayerActionTracker>()
         .init_resource::<ActionTimer>()
+        .init_resource::<PendingTurn>()
+        .init_resource::<GeneratedLevels>()
+        .init_resource::<LevelReload>()
+        .init_resource::<UndoHistory>()
         .init_resource::<PrevState>()
         .init_resource::<DebugSkipPlayerAction>()
+        .init_resource::<LogPathOverlay>()
+        .insert_resource(Difficulty::from_args())
         .add_event::<PlayerBirdRescueEvent>()
+        .add_event::<PlayerBevyDeliveryEvent>()
         .add_event::<CollisionEvent>()
         .add_event::<GameMessage>()
         .insert_resource(ClearColor(Color::srgb(0.5, 0.5, 0.9)))
         .add_systems(Startup, setup_initial_app_state) // Go directly to InGame for this demo
+        .add_systems(Startup, setup_spawn_warning_assets)
+        .add_systems(OnExit(AppState::AssetLoading), load_levels)
+        .add_systems(
+            Update,
+            (hot_reload_levels, finish_level_checks)
+                .chain()
+                .run_if(resource_exists::<LevelAssets>),
+        )
         // Startup and AppState transitions
         .add_systems(
             OnEnter(AppState::InGame),
             (text_update_game_message_hide, setup_game_environment).chain(),
         )
-        // GameState: PlayerIdle
+        // GameState: PlayerIdle. The transition runs `advance_game_turn` on leaving
+        // GameTurnInProgress first, so the logs spawned here are on the new board.
         .add_systems(
             OnEnter(GameState::PlayerIdle),
-            (
-                player_idle_entry_message,
-                spawn_logs.after(advance_game_turn),
-            ),
+            (player_idle_entry_message, record_turn, spawn_logs),
         )
         .add_systems(
             Update,
-            (handle_player_input, no_shadow_bird_on_gltf_butcher)
+            (
+                apply_level_reload.after(finish_level_checks),
+                undo_turns,
+                telegraph_spawns.run_if(resource_changed::<Game>),
+                handle_player_input,
+                no_shadow_bird_on_gltf_butcher,
+            )
+                .chain()
                 .run_if(in_state(GameState::PlayerIdle)),
         )
         // GameState: PlayerActionInProgress
@@ -323,9 +393,7 @@ fn main() {
         )
         .add_systems(
             Update,
-            (process_player_action, update_player_pos_after_jump)
-                .chain()
-                .run_if(in_state(GameState::PlayerActionInProgress)),
+            process_player_action.run_if(in_state(GameState::PlayerActionInProgress)),
         )
         .add_systems(
             OnExit(GameState::PlayerActionInProgress),
@@ -338,7 +406,14 @@ fn main() {
         )
         .add_systems(
             Update,
-            (process_game_turn, roll_logs, collision_detection_system)
+            (
+                process_game_turn,
+                fly_birds,
+                convey_player,
+                roll_logs,
+                collision_detection_system,
+                log_rock_collision_handling_system,
+            )
                 .chain()
                 .run_if(in_state(GameState::GameTurnInProgress)),
         )
@@ -360,6 +435,21 @@ fn main() {
                 .run_if(in_state(AppState::WinGame)),
         )
         .add_systems(OnExit(AppState::WinGame), cleanup_game)
+        // Campaign complete
+        .add_systems(
+            OnEnter(AppState::CampaignComplete),
+            campaign_complete_message,
+        )
+        .add_systems(
+            Update,
+            restart_campaign_on_input.run_if(in_state(AppState::CampaignComplete)),
+        )
+        .add_systems(
+            Update,
+            finish_level_generation
+                .run_if(in_state(AppState::WinGame).or(in_state(AppState::CampaignComplete))),
+        )
+        .add_systems(OnExit(AppState::CampaignComplete), cleanup_game)
         // End game
         .add_systems(
             Update,
@@ -377,11 +467,20 @@ fn main() {
             Update,
             (
                 rotate_system,
+                shake_rocks,
+                pulse_spawn_warnings,
                 update_aabb_system,
                 draw_aabb_gizmos,
-                player_collision_handling_system,
+                player_collision_handling_system.after(process_game_turn),
+                knock_back_player,
+                flash_invulnerable_player,
                 toggle_debug_skip_player_action,
+                toggle_log_paths,
+                draw_log_paths,
                 text_update_bird_count,
+                text_update_lives,
+                text_update_score,
+                text_update_level,
             )
                 .chain()
                 .run_if(in_state(AppState::InGame)),
@@ -397,12 +496,15 @@ fn main() {
         .add_systems(Update, log_gamestate_transitions)
         .add_observer(player_wins_trigger)
         .add_observer(increment_bevy)
+        .add_observer(bank_bevy)
         .add_observer(text_update_game_message)
         .run();
 }
 
 const TILE_SIZE: f32 = 1.0;
 const TILE_HALF_SIZE: f32 = 0.5;
+/// How far above the ground a player riding a log stands
+const RIDE_HEIGHT: f32 = TILE_HALF_SIZE;
 
 #[derive(Component)]
 pub struct Player;
@@ -410,15 +512,41 @@ pub struct Player;
 #[derive(Component)]
 pub struct PlayerEnd(pub Timer);
 
+/// The player was hit and is thrown out of the log's way.
+#[derive(Component)]
+pub struct Knockback {
+    pub start_position: Vec3,
+    pub target_position: Vec3,
+    pub timer: Timer,
+}
+
+/// The player is carried by a rolling log for the current turn.
+#[derive(Component)]
+pub struct Riding {
+    pub log: Entity,
+    /// Player position relative to the log
+    pub offset: Vec3,
+}
+
 #[derive(Component)]
 pub struct PlayerWin(pub Timer);
 
 #[derive(Component)]
-pub struct Log;
+pub struct Log {
+    /// `SimLog.id` of the log in `Game.board`
+    pub id: u32,
+}
 
 #[derive(Component)]
 pub struct Tile;
 
+#[derive(Component)]
+pub struct Rock;
+
+/// Where the rescued birds are carried to, they are stacked on it once banked.
+#[derive(Component)]
+pub struct Nest;
+
 #[derive(Component)]
 pub struct Bird;
 
@@ -431,7 +559,16 @@ pub struct GameMessage;
 #[derive(Component)]
 pub struct BirdCountText;
 
-#[derive(Clone, Reflect, Debug, PartialEq, Default)]
+#[derive(Component)]
+pub struct LivesText;
+
+#[derive(Component)]
+pub struct ScoreText;
+
+#[derive(Component)]
+pub struct LevelText;
+
+#[derive(Clone, Copy, Reflect, Debug, PartialEq, Eq, Hash, Default, serde::Deserialize)]
 pub enum Direction {
     #[default]
     None,
@@ -524,6 +661,76 @@ fn setup_initial_app_state(
                 TextColor(GOLD.into()),
             ),
             BirdCountText,
+        ))
+        .with_child((
+            TextSpan::new("   Lives: "),
+            TextFont {
+                font_size: 24.0,
+                ..default()
+            },
+        ))
+        .with_child((
+            TextSpan::new("0"),
+            (
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(GOLD.into()),
+            ),
+            LivesText,
+        ));
+
+    commands
+        .spawn((
+            Text::new("Level: "),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(42.0),
+                left: Val::Px(12.0),
+                ..default()
+            },
+            TextFont {
+                font_size: 24.0,
+                ..default()
+            },
+        ))
+        .with_child((
+            TextSpan::new("1"),
+            (
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(GOLD.into()),
+            ),
+            LevelText,
+        ));
+
+    commands
+        .spawn((
+            Text::new("Score: "),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(72.0),
+                left: Val::Px(12.0),
+                ..default()
+            },
+            TextFont {
+                font_size: 24.0,
+                ..default()
+            },
+        ))
+        .with_child((
+            TextSpan::new("0"),
+            (
+                TextFont {
+                    font_size: 24.0,
+                    ..default()
+                },
+                TextColor(GOLD.into()),
+            ),
+            ScoreText,
         ));
 
     commands.spawn((
@@ -543,6 +750,23 @@ fn setup_initial_app_state(
         Visibility::Hidden,
     ));
 
+    commands.spawn((
+        Text::new(""),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(12.0),
+            left: Val::Px(12.0),
+            ..default()
+        },
+        TextFont {
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(RED.into()),
+        LevelErrorText,
+        Visibility::Hidden,
+    ));
+
     commands.insert_resource(game);
 
     // In a real game, you might have a menu system here.
@@ -558,22 +782,20 @@ fn setup_game_environment(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut next_game_state: ResMut<NextState<GameState>>,
+    mut pending_turn: ResMut<PendingTurn>,
+    mut reload: ResMut<LevelReload>,
+    mut undo_history: ResMut<UndoHistory>,
 ) {
     info!("Entered AppState::InGame. Setting initial GameState to PlayerIdle.");
 
-    for y in 0..game.board_size_y {
-        for x in 0..game.board_size_x {
-            commands.spawn((
-                Tile,
-                Transform::from_xyz(x as f32, 0.0, y as f32),
-                Mesh3d(meshes.add(Plane3d::new(Vec3::Y, Vec2::splat(TILE_HALF_SIZE)))),
-                MeshMaterial3d(materials.add(Color::linear_rgb(0.51, 0.54, 0.075))),
-                ShowAabbGizmo {
-                    color: Some(Color::linear_rgba(0.51, 0.34, 0.075, 0.75)),
-                },
-            ));
-        }
-    }
+    let board = Simulation::new(&game).start();
+    game.board = board;
+    pending_turn.0 = None;
+    reload.0 = None;
+    undo_history.clear();
+    undo_history.record(&game);
+
+    spawn_tiles(&mut commands, &game, &mut meshes, &mut materials);
 
     let Some(bg_gltf) = assets_gltf.get(&model_assets.background.clone()) else {
         return;
@@ -591,31 +813,33 @@ fn setup_game_environment(
     ));
 
     // birds
-    for (k, v) in game.current_level_mut().bird_map.iter_mut() {
-        let entity = commands
-            .spawn((
-                Bird,
-                Transform::from_translation(k.as_vec3())
-                    .with_rotation(Quat::from_rotation_y(PI))
-                    .with_scale(Vec3::splat(0.2)),
-                SceneRoot(bird_gltf.scenes[0].clone()),
-            ))
-            .with_children(|parent| {
-                // Shadow
-                parent.spawn((
-                    Mesh3d(meshes.add(Sphere::new(1.0))),
-                    MeshMaterial3d(materials.add(Color::linear_rgb(0.1, 0.0, 0.1))),
-                    Transform::from_xyz(0.0, -10.0, 0.0).with_scale(Vec3::new(1.0, 0.2, 1.0)),
-                ));
-            })
-            .id();
-        *v = entity;
+    let birds = game.board.birds.clone();
+    let level = game.current_level_mut();
+    level.bird_map.clear();
+    for bird in birds.iter() {
+        let spawn = &level.birds[bird.spawn];
+        let entity = spawn_bird(
+            &mut commands,
+            spawn,
+            bird.pos,
+            bird_gltf,
+            &mut meshes,
+            &mut materials,
+        );
+        level.bird_map.insert(bird_tile(bird.pos), entity);
+    }
+
+    for rock in game.current_level().rocks.iter() {
+        spawn_rock(&mut commands, *rock, &mut meshes, &mut materials);
+    }
+    if let Some(nest) = game.current_level().nest {
+        spawn_nest(&mut commands, nest, &mut meshes, &mut materials);
     }
 
     // Player
     commands
         .spawn((
-            Transform::from_xyz(6.0, TILE_HALF_SIZE, 0.0),
+            Transform::from_xyz(game.start.x as f32, TILE_HALF_SIZE, game.start.y as f32),
             Player,
             Mesh3d(meshes.add(Cuboid::new(
                 TILE_HALF_SIZE,
@@ -652,6 +876,133 @@ fn setup_game_environment(
     // You could also spawn game entities, UI, etc.
 }
 
+/// The key of a bird on `tile` in `Level.bird_map`, also its world position.
+fn bird_tile(tile: IVec2) -> IVec3 {
+    IVec3::new(tile.x, BIRD_Y, tile.y)
+}
+
+fn spawn_bird(
+    commands: &mut Commands,
+    spawn: &BirdSpawn,
+    tile: IVec2,
+    bird_gltf: &Gltf,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) -> Entity {
+    commands
+        .spawn((
+            Bird,
+            spawn.kind,
+            Name::new(spawn.name.clone().unwrap_or_else(|| "bird".to_string())),
+            Transform::from_translation(bird_tile(tile).as_vec3())
+                .with_rotation(Quat::from_rotation_y(PI))
+                .with_scale(Vec3::splat(spawn.kind.scale())),
+            SceneRoot(bird_gltf.scenes[0].clone()),
+        ))
+        .with_children(|parent| {
+            // Shadow
+            parent.spawn((
+                Mesh3d(meshes.add(Sphere::new(1.0))),
+                MeshMaterial3d(materials.add(Color::linear_rgb(0.1, 0.0, 0.1))),
+                Transform::from_xyz(0.0, -10.0, 0.0).with_scale(Vec3::new(1.0, 0.2, 1.0)),
+            ));
+        })
+        .id()
+}
+
+/// Spawns a tile of the board for every tile, in the colour of its [`TileKind`].
+fn spawn_tiles(
+    commands: &mut Commands,
+    game: &Game,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) {
+    let tiles = &game.current_level().tiles;
+    for y in 0..game.board_size_y {
+        for x in 0..game.board_size_x {
+            let kind = tiles.get(IVec2::new(x as i32, y as i32));
+            let mut tile = commands.spawn((
+                Tile,
+                Transform::from_xyz(x as f32, 0.0, y as f32),
+                Mesh3d(meshes.add(Plane3d::new(Vec3::Y, Vec2::splat(TILE_HALF_SIZE)))),
+                MeshMaterial3d(materials.add(kind.color())),
+                ShowAabbGizmo {
+                    color: Some(Color::linear_rgba(0.51, 0.34, 0.075, 0.75)),
+                },
+            ));
+            if let TileKind::Conveyor(dir) = kind {
+                // A stripe from the middle of the tile to the edge it pushes towards
+                let dir = dir.value().as_vec3();
+                tile.with_children(|parent| {
+                    parent.spawn((
+                        Transform::from_translation(dir * TILE_HALF_SIZE * 0.5 + Vec3::Y * 0.01),
+                        Mesh3d(meshes.add(Cuboid::from_size(
+                            Vec3::new(0.1, 0.01, 0.1) + dir.abs() * TILE_HALF_SIZE * 0.8,
+                        ))),
+                        MeshMaterial3d(materials.add(Color::linear_rgb(0.9, 0.75, 0.1))),
+                    ));
+                });
+            }
+        }
+    }
+}
+
+fn spawn_rock(
+    commands: &mut Commands,
+    tile: IVec2,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) {
+    let center = Vec3::new(tile.x as f32 * TILE_SIZE, 0.3, tile.y as f32 * TILE_SIZE);
+    commands.spawn((
+        Rock,
+        Transform::from_translation(center).with_scale(Vec3::new(0.45, 0.35, 0.42)),
+        Mesh3d(meshes.add(Sphere::new(1.0))),
+        MeshMaterial3d(materials.add(Color::linear_rgb(0.3, 0.3, 0.32))),
+        // Reaches over the tile edge so a log stopped in front of the rock touches it
+        LLAabb3d::new(
+            center,
+            Vec3::new(TILE_HALF_SIZE * 0.8, TILE_HALF_SIZE, TILE_HALF_SIZE * 1.5),
+        ),
+        GameObjectType::Rock,
+    ));
+}
+
+fn spawn_nest(
+    commands: &mut Commands,
+    tile: IVec2,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+) -> Entity {
+    commands
+        .spawn((
+            Nest,
+            Transform::from_xyz(tile.x as f32 * TILE_SIZE, 0.1, tile.y as f32 * TILE_SIZE)
+                .with_scale(Vec3::new(1.0, 0.5, 1.0)),
+            Mesh3d(meshes.add(Torus::new(0.2, TILE_HALF_SIZE * 0.9))),
+            MeshMaterial3d(materials.add(Color::linear_rgb(0.45, 0.28, 0.12))),
+        ))
+        .id()
+}
+
+/// Puts a rescued bird `idx` birds up the stack on the player or the nest.
+fn stack_bird(transform: &mut Transform, idx: usize) {
+    let height = (idx + 1) as f32;
+    transform.translation = Vec3::new(0.0, 0.5 * height, 0.0);
+    transform.rotation = Quat::from_rotation_y(PI + 0.65 * height);
+}
+
+/// Spawn indices of the birds stacked on the player, bottom first. On a level without a
+/// nest the banked birds stay on the player.
+fn player_bevy(level: &Level, board: &Board) -> Vec<usize> {
+    let banked = if level.nest.is_none() {
+        board.delivered.as_slice()
+    } else {
+        &[]
+    };
+    banked.iter().chain(board.carried.iter()).copied().collect()
+}
+
 fn no_shadow_bird_on_gltf_butcher(
     birds: Query<Entity, With<Bird>>,
     children: Query<&Children>,
@@ -666,7 +1017,7 @@ fn no_shadow_bird_on_gltf_butcher(
 }
 
 #[allow(dead_code)]
-const PALETTE: [&'static str; SEQUENCE_LENGTH] = [
+const PALETTE: [&'static str; 8] = [
     "#FF8383", "#FFF574", "#A1D6CB", "#A19AD3", "#ca5a2e", "#FFF574", "#A1D6CB", "#A19AD3",
 ];
 
@@ -702,80 +1053,422 @@ fn hex_to_srgb(hex: &str) -> Result<(f32, f32, f32), String> {
     Ok((r_srgb, g_srgb, b_srgb))
 }
 
-fn format_array_with_bracket<T: std::fmt::Display>(arr: &[T], index: usize) -> String {
-    let mut parts = Vec::new();
-    for (i, value) in arr.iter().enumerate() {
-        if i == index {
-            parts.push(format!("[{}]", value));
-        } else {
-            parts.push(format!("{}", value));
-        }
+/// World position of a log, the model is centered on the tiles it covers.
+fn log_translation(log: &SimLog) -> Vec3 {
+    let center = log.center() * TILE_SIZE;
+    Vec3::new(center.x, TILE_HALF_SIZE * 0.5, center.y)
+}
+
+/// World position of the player standing on `tile`, on top of a log if `riding`.
+fn player_translation(tile: IVec2, riding: bool) -> Vec3 {
+    let height = if riding { RIDE_HEIGHT } else { 0.0 };
+    Vec3::new(
+        tile.x as f32 * TILE_SIZE,
+        TILE_HALF_SIZE + height,
+        tile.y as f32 * TILE_SIZE,
+    )
+}
+
+/// The log model lies along X, logs rolling along X are turned to lie along Z.
+fn log_rotation(log: &SimLog) -> Quat {
+    if log.dir.x != 0 {
+        Quat::from_rotation_y(FRAC_PI_2)
+    } else {
+        Quat::IDENTITY
     }
-    parts.join(", ")
 }
 
+/// Spawns an entity for every log of `Game.board` that doesn't have one yet.
 fn spawn_logs(
     mut commands: Commands,
-    mut game: ResMut<Game>,
+    game: Res<Game>,
     // mut meshes: ResMut<Assets<Mesh>>,
     // mut materials: ResMut<Assets<StandardMaterial>>,
     model_assets: Res<ModelAssets>,
     assets_gltf: Res<Assets<Gltf>>,
+    log_query: Query<&Log>,
 ) {
-    let current_level = game.current_level;
-    let current_step = game.current_step;
-    let board_size_y = game.board_size_y;
     let Some(log_gltf) = assets_gltf.get(&model_assets.log.clone()) else {
         return;
     };
-    for seq in game.levels[current_level].seq.iter_mut() {
-        // check for every nth step
-        let seq_idx = seq.seq_idx(current_step);
-        info!("<<<< step: {}", current_step);
-        if seq.log_sequence_nth_step[seq_idx as usize] > 0 {
-            // update the sequence index
-            seq.sequence_index = seq.seq_idx(current_step);
-            seq.debug_print();
-
-            let top_of_board_minus_offsetz = board_size_y as f32 - seq.get_spawn_offset_z() as f32;
-
-            let log_center = Vec3::new(
-                seq.get_spawn_offset_x() as f32 - TILE_HALF_SIZE,
-                TILE_HALF_SIZE * 0.5,
-                top_of_board_minus_offsetz,
-            );
-            commands.spawn((
-                Log,
-                Transform::from_translation(log_center.clone()).with_scale(Vec3::new(
-                    TILE_SIZE * 2.0,
-                    TILE_HALF_SIZE,
-                    TILE_HALF_SIZE,
-                )),
-                LLAabb3d::new(
-                    log_center,
-                    Vec3::new(TILE_SIZE * 2.0, TILE_HALF_SIZE * 0.5, TILE_HALF_SIZE * 0.5),
-                ),
-                // LLShowAabbGizmo {
-                //     color: Some(Color::linear_rgba(0.51, 0.34, 0.075, 0.75)),
-                // },
-                SceneRoot(log_gltf.scenes[0].clone()),
-                // Mesh3d(meshes.add(Cuboid::new(TILE_SIZE * 4.0, TILE_HALF_SIZE, TILE_HALF_SIZE))),
-                // MeshMaterial3d(materials.add(hex_to_color(PALETTE[seq_idx as usize]))),
-                GameObjectType::Log,
-            ));
+    let spawned: Vec<u32> = log_query.iter().map(|log| log.id).collect();
+    info!("<<<< step: {}", game.board.step);
+    for log in game.board.logs.iter() {
+        if spawned.contains(&log.id) {
+            continue;
         }
-        info!(">>>> seq finished: {}", seq.sequence_index);
+        game.current_level().seq[log.seq].debug_print();
+
+        spawn_log(&mut commands, log, log_gltf);
     }
 }
 
-fn advance_game_turn(mut game: ResMut<Game>) {
-    game.current_step += 1;
-    info!("Advancing to turn number: {}", game.current_step);
+/// Tints a tile a log spawns on at the end of the turn.
+#[derive(Component)]
+pub struct SpawnWarning;
+
+/// Mesh and material shared by every [`SpawnWarning`], so the warnings of each turn
+/// don't add new assets.
+#[derive(Resource)]
+struct SpawnWarningAssets {
+    mesh: Handle<Mesh>,
+    material: Handle<StandardMaterial>,
 }
 
-fn endgame_message_update(mut text: Single<&mut Text, With<GameMessage>>) {
-    text.clear();
-    text.push_str("GAME OVER");
+fn setup_spawn_warning_assets(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(SpawnWarningAssets {
+        mesh: meshes.add(Plane3d::new(Vec3::Y, Vec2::splat(TILE_HALF_SIZE * 0.9))),
+        material: materials.add(StandardMaterial {
+            base_color: Color::linear_rgba(0.9, 0.1, 0.05, 0.5),
+            alpha_mode: AlphaMode::Blend,
+            unlit: true,
+            ..default()
+        }),
+    });
+}
+
+/// Marks the tiles of the logs the coming turn spawns, they come from the same sequence
+/// evaluation as the spawn itself.
+fn telegraph_spawns(
+    mut commands: Commands,
+    game: Res<Game>,
+    warning_query: Query<Entity, With<SpawnWarning>>,
+    warning_assets: Res<SpawnWarningAssets>,
+) {
+    for entity in warning_query.iter() {
+        commands.entity(entity).despawn();
+    }
+    let sim = Simulation::new(&game);
+    let spawns = sim.next_spawns(&game.board);
+    for tile in spawns
+        .iter()
+        .flat_map(|log| log.tiles())
+        .filter(|tile| sim.on_board(*tile))
+    {
+        commands.spawn((
+            SpawnWarning,
+            Transform::from_xyz(tile.x as f32 * TILE_SIZE, 0.02, tile.y as f32 * TILE_SIZE),
+            Mesh3d(warning_assets.mesh.clone()),
+            MeshMaterial3d(warning_assets.material.clone()),
+            NotShadowCaster,
+        ));
+    }
+}
+
+fn pulse_spawn_warnings(
+    time: Res<Time>,
+    warning_query: Query<(), With<SpawnWarning>>,
+    warning_assets: Res<SpawnWarningAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    const PULSES_PER_SECOND: f32 = 1.5;
+    let alpha = 0.35 + 0.25 * (time.elapsed_secs() * PULSES_PER_SECOND * 2.0 * PI).sin();
+    if warning_query.is_empty() {
+        return;
+    }
+    // All the warnings share their material
+    if let Some(material) = materials.get_mut(&warning_assets.material) {
+        material.base_color.set_alpha(alpha);
+    }
+}
+
+fn spawn_log(commands: &mut Commands, log: &SimLog, log_gltf: &Gltf) {
+    // The log model is 2 units long
+    let half_length = TILE_SIZE * log.length as f32 * 0.5;
+    let log_center = log_translation(log);
+    let axis = log.axis().as_vec2();
+    let half_extents = Vec3::new(
+        axis.x * half_length + (1.0 - axis.x) * TILE_HALF_SIZE * 0.5,
+        TILE_HALF_SIZE * 0.5,
+        axis.y * half_length + (1.0 - axis.y) * TILE_HALF_SIZE * 0.5,
+    );
+    commands.spawn((
+        Log { id: log.id },
+        Transform::from_translation(log_center)
+            .with_rotation(log_rotation(log))
+            .with_scale(Vec3::new(half_length, TILE_HALF_SIZE, TILE_HALF_SIZE)),
+        LLAabb3d::new(log_center, half_extents),
+        // LLShowAabbGizmo {
+        //     color: Some(Color::linear_rgba(0.51, 0.34, 0.075, 0.75)),
+        // },
+        SceneRoot(log_gltf.scenes[0].clone()),
+        // Mesh3d(meshes.add(Cuboid::new(TILE_SIZE * 4.0, TILE_HALF_SIZE, TILE_HALF_SIZE))),
+        // MeshMaterial3d(materials.add(hex_to_color(PALETTE[seq_idx as usize]))),
+        GameObjectType::Log,
+    ));
+}
+
+/// The entities that show a [`Board`], to put a different board on screen.
+#[derive(SystemParam)]
+pub struct BoardEntities<'w, 's> {
+    commands: Commands<'w, 's>,
+    model_assets: Res<'w, ModelAssets>,
+    assets_gltf: Res<'w, Assets<Gltf>>,
+    meshes: ResMut<'w, Assets<Mesh>>,
+    materials: ResMut<'w, Assets<StandardMaterial>>,
+    log_query: Query<'w, 's, Entity, With<Log>>,
+    bird_query: Query<'w, 's, Entity, With<Bird>>,
+    tile_query: Query<'w, 's, Entity, With<Tile>>,
+    rock_query: Query<'w, 's, Entity, With<Rock>>,
+    nest_query: Query<'w, 's, Entity, With<Nest>>,
+    player_query: Query<'w, 's, Entity, With<Player>>,
+}
+
+impl BoardEntities<'_, '_> {
+    /// Respawns the tiles, logs, rocks, nest and birds of `board` and makes it the board of
+    /// `game`. The player is left where it is, with the bevy of `board` on top.
+    pub fn rebuild(&mut self, game: &mut Game, board: Board) {
+        let (Some(log_gltf), Some(bird_gltf)) = (
+            self.assets_gltf.get(&self.model_assets.log),
+            self.assets_gltf.get(&self.model_assets.bird),
+        ) else {
+            return;
+        };
+        let commands = &mut self.commands;
+
+        for entity in self.log_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        for log in board.logs.iter() {
+            spawn_log(commands, log, log_gltf);
+        }
+
+        for entity in self.tile_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        spawn_tiles(commands, game, &mut self.meshes, &mut self.materials);
+
+        for entity in self.rock_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        for rock in game.current_level().rocks.iter() {
+            spawn_rock(commands, *rock, &mut self.meshes, &mut self.materials);
+        }
+
+        for entity in self.nest_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        let nest = game
+            .current_level()
+            .nest
+            .map(|tile| spawn_nest(commands, tile, &mut self.meshes, &mut self.materials));
+
+        // Waiting, carried and banked birds alike
+        for entity in self.bird_query.iter() {
+            commands.entity(entity).despawn();
+        }
+        let level = game.current_level_mut();
+        level.bird_map.clear();
+        let stacks = [
+            (self.player_query.single().ok(), player_bevy(level, &board)),
+            (nest, board.delivered.clone()),
+        ];
+        for (parent, spawns) in stacks {
+            let Some(parent) = parent else {
+                continue;
+            };
+            for (idx, spawn) in spawns.iter().enumerate() {
+                let entity = spawn_bird(
+                    commands,
+                    &level.birds[*spawn],
+                    IVec2::ZERO,
+                    bird_gltf,
+                    &mut self.meshes,
+                    &mut self.materials,
+                );
+                let mut transform =
+                    Transform::from_scale(Vec3::splat(level.birds[*spawn].kind.scale()));
+                stack_bird(&mut transform, idx);
+                commands.entity(entity).insert((transform, ChildOf(parent)));
+            }
+        }
+        for bird in board.birds.iter() {
+            let entity = spawn_bird(
+                commands,
+                &level.birds[bird.spawn],
+                bird.pos,
+                bird_gltf,
+                &mut self.meshes,
+                &mut self.materials,
+            );
+            level.bird_map.insert(bird_tile(bird.pos), entity);
+        }
+        // Keeps the sequences in sync for `debug_print`
+        for (seq, state) in level.seq.iter_mut().zip(board.sequences.iter()) {
+            seq.set_state(*state);
+        }
+        game.board = board;
+    }
+}
+
+/// Rebuilds the board after the current level's file was edited. The logs are replayed up
+/// to the current step and the player keeps their tile and rescued birds, unless a rescued
+/// bird is gone from the new version, no bird is left or the player's tile turned into a
+/// rock or water they aren't riding a log over, then the level restarts.
+fn apply_level_reload(
+    mut reload: ResMut<LevelReload>,
+    mut game: ResMut<Game>,
+    mut board_entities: BoardEntities,
+    mut undo_history: ResMut<UndoHistory>,
+    mut player: Single<&mut Transform, With<Player>>,
+) {
+    let Some(previous_birds) = reload.0.take() else {
+        return;
+    };
+    let sim = Simulation::new(&game);
+    // Birds are told apart by their tile in the level file
+    let respawn = |spawns: &[usize]| -> Option<Vec<usize>> {
+        spawns
+            .iter()
+            .map(|spawn| {
+                let pos = previous_birds.get(*spawn)?;
+                sim.level.birds.iter().position(|bird| bird.pos == *pos)
+            })
+            .collect()
+    };
+    let carried = respawn(&game.board.carried);
+    let delivered = respawn(&game.board.delivered);
+
+    let mut board = sim.board_at_step(game.board.step);
+    board.player = game.board.player;
+    // An edit lowering the level's lives can't leave the player with more than it allows
+    board.lives = game.board.lives.min(sim.lives);
+    board.invulnerable = game.board.invulnerable;
+    board.riding = board
+        .logs
+        .iter()
+        .find(|log| log.covers(board.player))
+        .map(|log| log.id);
+    // Moving birds go where they would be with the player waiting at the start
+    let keeps_rescued = if let (Some(carried), Some(delivered)) = (carried, delivered) {
+        board
+            .birds
+            .retain(|bird| !carried.contains(&bird.spawn) && !delivered.contains(&bird.spawn));
+        board.carried = carried;
+        board.delivered = delivered;
+        true
+    } else {
+        false
+    };
+    let tile = board.player;
+    let blocked =
+        sim.is_rock(tile) || (sim.tile_kind(tile) == TileKind::Water && board.riding.is_none());
+    let board = if keeps_rescued && !board.birds.is_empty() && !blocked {
+        info!("Rebuilt the level at turn number: {}", board.step);
+        board
+    } else {
+        info!("The level changed too much to carry on, restarting it");
+        let board = sim.start();
+        player.translation = Vec3::new(
+            game.start.x as f32 * TILE_SIZE,
+            TILE_HALF_SIZE,
+            game.start.y as f32 * TILE_SIZE,
+        );
+        // The birds rescued on this level go back to their tiles
+        game.bevy_count = game.level_start_bevy_count;
+        game.score = Score::banked(game.score.total);
+        board
+    };
+
+    board_entities.rebuild(&mut game, board);
+    // The earlier turns were played on the old level
+    undo_history.clear();
+    undo_history.record(&game);
+}
+
+/// Applies the pending turn to `Game.board` once its animation has played out.
+fn advance_game_turn(
+    mut game: ResMut<Game>,
+    mut pending_turn: ResMut<PendingTurn>,
+    action_timer: Res<ActionTimer>,
+    player_query: Query<Option<&Children>, With<Player>>,
+    mut bird_query: Query<&mut Transform, With<Bird>>,
+    mut commands: Commands,
+) {
+    // Pausing also leaves GameTurnInProgress, the turn carries on after resuming
+    if !action_timer.0.finished() {
+        return;
+    }
+    let Some(turn) = pending_turn.0.take() else {
+        return;
+    };
+    // Keeps the sequences in sync for `debug_print`
+    for (seq, state) in game
+        .current_level_mut()
+        .seq
+        .iter_mut()
+        .zip(turn.board.sequences.iter())
+    {
+        seq.set_state(*state);
+    }
+    // The birds that moved are keyed by their new tiles
+    let mut bird_map = std::mem::take(&mut game.current_level_mut().bird_map);
+    let moved: Vec<(IVec2, IVec2)> = game
+        .board
+        .birds
+        .iter()
+        .filter_map(|bird| {
+            let next = turn
+                .board
+                .birds
+                .iter()
+                .find(|next| next.spawn == bird.spawn)?;
+            (next.pos != bird.pos).then_some((bird.pos, next.pos))
+        })
+        .collect();
+    let flown: Vec<(IVec2, Entity)> = moved
+        .into_iter()
+        .filter_map(|(from, to)| Some((to, bird_map.remove(&bird_tile(from))?)))
+        .collect();
+    for (to, entity) in flown {
+        bird_map.insert(bird_tile(to), entity);
+    }
+    // The carried birds are the top of the bevy, a hit knocks them off the player and the
+    // last one is the one dropped
+    let leaving: Vec<SimBird> = turn
+        .scattered
+        .iter()
+        .chain(turn.dropped.iter())
+        .copied()
+        .collect();
+    let bevy: Vec<Entity> = player_query
+        .iter()
+        .flatten()
+        .flatten()
+        .copied()
+        .filter(|child| bird_query.contains(*child))
+        .collect();
+    let carried = &bevy[bevy.len().saturating_sub(leaving.len())..];
+    for (entity, bird) in carried.iter().zip(leaving.iter()) {
+        commands.entity(*entity).remove::<ChildOf>();
+        if let Ok(mut transform) = bird_query.get_mut(*entity) {
+            transform.translation = bird_tile(bird.pos).as_vec3();
+            transform.rotation = Quat::from_rotation_y(PI);
+        }
+        bird_map.insert(bird_tile(bird.pos), *entity);
+    }
+    game.current_level_mut().bird_map = bird_map;
+    if turn.dropped.is_some() {
+        game.bevy_count = game.bevy_count.saturating_sub(1);
+        game.score.drop_bird();
+    }
+    if !turn.scattered.is_empty() {
+        info!("{} birds scattered", turn.scattered.len());
+        game.bevy_count = game.bevy_count.saturating_sub(turn.scattered.len() as u32);
+        game.score.scatter();
+    }
+
+    game.board = turn.board;
+    info!("Advancing to turn number: {}", game.board.step);
+}
+
+fn endgame_message_update(mut text: Single<&mut Text, With<GameMessage>>) {
+    text.clear();
+    text.push_str("GAME OVER");
 }
 
 fn player_end_handling_system(
@@ -805,20 +1498,18 @@ pub fn player_wins_trigger(
     mut next_app_state: ResMut<NextState<AppState>>,
     mut commands: Commands,
 ) {
-    match trigger.event() {
-        GameEvent::Win => {
-            info!("winner!");
-            let Ok(entity) = query.single_mut() else {
-                return;
-            };
-            commands
-                .entity(entity)
-                .insert_if_new(PlayerWin(Timer::from_seconds(2.6, TimerMode::Once)));
+    let GameEvent::Win { .. } = trigger.event() else {
+        return;
+    };
+    info!("winner!");
+    let Ok(entity) = query.single_mut() else {
+        return;
+    };
+    commands
+        .entity(entity)
+        .insert_if_new(PlayerWin(Timer::from_seconds(2.6, TimerMode::Once)));
 
-            next_app_state.set(AppState::WinGame);
-        }
-        _ => (),
-    }
+    next_app_state.set(AppState::WinGame);
 }
 
 fn update_win_timer(
@@ -826,16 +1517,56 @@ fn update_win_timer(
     time: Res<Time>,
     mut next_app_state: ResMut<NextState<AppState>>,
     mut game: ResMut<Game>,
+    generated: Res<GeneratedLevels>,
+    mut rng: GlobalEntropy<WyRand>,
+    mut commands: Commands,
 ) {
     for mut timer in query.iter_mut() {
-        if timer.0.tick(time.delta()).finished() {
-            // This should be where we advance to the next level
-            *game = Game::default();
-            next_app_state.set(AppState::InGame);
+        if timer.0.tick(time.delta()).just_finished() {
+            // Once past the campaign every win brings a new generated level, the game
+            // moves on to it from `finish_level_generation`
+            if game.current_level_index() + 1 == game.levels.len() && !generated.0.is_empty() {
+                commands.spawn(LevelGeneration::start(&game, &generated, rng.next_u64()));
+                continue;
+            }
+            // The board itself is rebuilt by `cleanup_game` when leaving WinGame
+            if game.next_level() {
+                info!("Advancing to level {}", game.current_level_index() + 1);
+                next_app_state.set(AppState::InGame);
+            } else {
+                info!("Last level won, campaign complete!");
+                next_app_state.set(AppState::CampaignComplete);
+            }
         }
     }
 }
 
+fn campaign_complete_message(mut commands: Commands) {
+    commands.trigger(GameEvent::CampaignComplete);
+}
+
+fn restart_campaign_on_input(
+    input: Res<ButtonInput<KeyCode>>,
+    mut game: ResMut<Game>,
+    mut next_app_state: ResMut<NextState<AppState>>,
+    mut generated: ResMut<GeneratedLevels>,
+    mut rng: GlobalEntropy<WyRand>,
+    generations: Query<(), With<LevelGeneration>>,
+    mut commands: Commands,
+) {
+    if !generations.is_empty() {
+        return;
+    }
+    if input.just_pressed(KeyCode::Enter) {
+        info!("Restarting the campaign from the first level.");
+        generated.0.clear();
+        game.restart_campaign();
+        next_app_state.set(AppState::InGame);
+    } else if input.just_pressed(KeyCode::KeyG) {
+        commands.spawn(LevelGeneration::start(&game, &generated, rng.next_u64()));
+    }
+}
+
 fn cleanup_game(
     mut commands: Commands,
     game_entities: Query<
@@ -844,17 +1575,25 @@ fn cleanup_game(
             With<Player>,
             With<Log>,
             With<Tile>,
+            With<Rock>,
+            With<Nest>,
+            With<SpawnWarning>,
             With<PlayerCursor>,
             With<Bird>,
         )>,
     >,
     mut game: ResMut<Game>,
+    level_assets: Res<LevelAssets>,
+    levels: Res<Assets<Level>>,
+    generated: Res<GeneratedLevels>,
 ) {
     for entity in game_entities.iter() {
         commands.entity(entity).despawn();
     }
-    info!("reseting game");
-    *game = Game::default();
+    info!("reseting level {}", game.current_level_index() + 1);
+    let mut levels = level_assets.clone_levels(&levels);
+    levels.extend(generated.0.iter().cloned());
+    game.restart_level(levels);
 }
 
 fn update_aabb_system(mut query: Query<(&Transform, &mut LLAabb3d)>) {
@@ -900,38 +1639,89 @@ fn handle_player_input(
     mut next_game_state: ResMut<NextState<GameState>>,
     mut player_action_tracker: ResMut<PlayerActionTracker>,
     debug: Res<DebugSkipPlayerAction>,
+    game: Res<Game>,
+    mut pending_turn: ResMut<PendingTurn>,
+    knocked_back: Query<(), (With<Player>, With<Knockback>)>,
 ) {
-    let mut moving: bool = false;
-    let mut jump: bool = false;
-    if input.just_pressed(KeyCode::KeyW) {
-        moving = true;
-        player_action_tracker.is_moving = Direction::North;
+    // Wait for the player to land after a hit
+    if !knocked_back.is_empty() {
+        return;
+    }
+    let dir = if input.just_pressed(KeyCode::KeyW) {
+        Some(Direction::North)
     } else if input.just_pressed(KeyCode::KeyD) {
-        moving = true;
-        player_action_tracker.is_moving = Direction::West;
+        Some(Direction::West)
     } else if input.just_pressed(KeyCode::KeyS) {
-        moving = true;
-        player_action_tracker.is_moving = Direction::South;
+        Some(Direction::South)
     } else if input.just_pressed(KeyCode::KeyA) {
-        moving = true;
-        player_action_tracker.is_moving = Direction::East;
+        Some(Direction::East)
+    } else {
+        None
+    };
+    // Holding shift jumps in the direction instead of walking
+    let jumping = input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    let action = if let Some(dir) = dir {
+        Some(if jumping {
+            Action::Jump(dir)
+        } else {
+            Action::Move(dir)
+        })
     } else if input.just_pressed(KeyCode::KeyJ) {
-        jump = true;
         info!("Player choo choo chooses to JUMP.");
-        player_action_tracker.is_jumping = true;
+        Some(Action::Jump(Direction::None))
+    } else if input.just_pressed(KeyCode::KeyQ) {
+        Some(Action::Drop)
+    } else if debug.skip_player_action {
+        Some(Action::Move(Direction::None))
+    } else {
+        None
     };
 
     if input.just_released(KeyCode::Escape) {
         std::process::exit(0)
     }
 
-    if moving {
-        info!("Player chooses MOVE.");
-        player_action_tracker.is_jumping = false;
+    let Some(action) = action else {
+        return;
     };
-    if moving | jump | debug.skip_player_action {
-        next_game_state.set(GameState::PlayerActionInProgress);
+    // Whatever was pressed, a player stuck in the mud waits the turn out
+    let action = if game.board.stuck {
+        info!("Player is stuck in the mud.");
+        Action::Move(Direction::None)
+    } else {
+        action
     };
+    match action {
+        Action::Move(dir) => {
+            if !game.is_valid_player_move(&dir) {
+                return;
+            }
+            info!("Player chooses MOVE.");
+            player_action_tracker.is_moving = dir;
+            player_action_tracker.is_jumping = false;
+        }
+        Action::Jump(dir) => {
+            if !game.is_valid_player_jump(&dir) {
+                return;
+            }
+            player_action_tracker.is_moving = dir;
+            player_action_tracker.is_jumping = true;
+        }
+        Action::Drop => {
+            if !game.is_valid_player_drop() {
+                return;
+            }
+            info!("Player chooses DROP.");
+            player_action_tracker.is_moving = Direction::None;
+            player_action_tracker.is_jumping = false;
+        }
+    }
+
+    let turn = Simulation::new(&game).step(&game.board, action);
+    info!("Turn {} outcome: {:?}", game.board.step, turn.outcome);
+    pending_turn.0 = Some(turn);
+    next_game_state.set(GameState::PlayerActionInProgress);
 }
 
 // Component for player movement animation
@@ -946,6 +1736,8 @@ fn setup_player_action_timer(
     player_action_tracker: Res<PlayerActionTracker>,
     mut commands: Commands,
     player_query: Query<(Entity, &Transform), With<Player>>,
+    pending_turn: Res<PendingTurn>,
+    game: Res<Game>,
 ) {
     let action_type = if player_action_tracker.is_jumping {
         "JUMP START"
@@ -956,23 +1748,32 @@ fn setup_player_action_timer(
         "GameState: PlayerActionInProgress ({action_type}). Starting {PLAYER_ACTION_ANIMATION_DURATION}s animation timer."
     );
     action_timer.0 = if player_action_tracker.is_jumping {
-        Timer::from_seconds(PLAYER_JUMP_ANIMATION_DURATION, TimerMode::Once)
+        Timer::from_seconds(
+            PLAYER_JUMP_ANIMATION_DURATION * jump_weight(&game),
+            TimerMode::Once,
+        )
     } else {
         Timer::from_seconds(PLAYER_ACTION_ANIMATION_DURATION, TimerMode::Once)
     };
 
     action_timer.0.reset(); // Ensure it's fresh
 
+    let Some(turn) = pending_turn.0.as_ref() else {
+        return;
+    };
+
     // Set up player movement animation
     if let Ok((player_entity, player_transform)) = player_query.single() {
         let start_position = player_transform.translation;
+        // A conveyor pushes the player on during the game turn
+        let tile = turn.pushed_from.unwrap_or(turn.board.player);
         let target_position = if player_action_tracker.is_jumping {
-            // Jump upward
-            Vec3::new(start_position.x, start_position.y + 1.0, start_position.z)
+            // Jump upward, to halfway between the start and landing tiles
+            let apex = (game.board.player + tile).as_vec2() * 0.5;
+            Vec3::new(apex.x, TILE_HALF_SIZE + 1.0 / jump_weight(&game), apex.y)
         } else {
-            // Move in direction
-            Vec3::new(start_position.x, start_position.y, start_position.z)
-                + player_action_tracker.is_moving.value().as_vec3()
+            // Move in direction, staying on top of a ridden log
+            player_translation(tile, turn.carried_by.is_some())
         };
 
         // Move player in direction
@@ -1033,14 +1834,9 @@ fn process_player_action(
     }
 }
 
-fn update_player_pos_after_jump(
-    mut game: ResMut<Game>,
-    player_query: Query<&PlayerMove, With<Player>>,
-) {
-    if let Ok(player_move) = player_query.single() {
-        game.player_pos = player_move.target_position.as_ivec3();
-        debug!("Player position updated to: {:?}", game.player_pos);
-    }
+/// The carried birds slow the player's jumps down and keep them low.
+fn jump_weight(game: &Game) -> f32 {
+    1.0 + BIRD_JUMP_WEIGHT * game.board.carried.len() as f32
 }
 
 // Cubic easing function for smoother animation
@@ -1057,12 +1853,35 @@ fn ease_in_out_cubic(t: f32) -> f32 {
 pub struct LogRoll {
     pub start_position: Vec3,  // Starting position of the log
     pub target_position: Vec3, // Target position after rolling
+    /// World axis the log spins around, across its roll
+    pub roll_axis: Vec3,
+    /// Rotation of the log when it stops rolling
+    pub rest_rotation: Quat,
+    /// Turn progress -> position, a knocked log waits until it is hit
+    pub keyframes: Vec<(f32, Vec3)>,
+}
+
+impl LogRoll {
+    pub fn position(&self, progress: f32) -> Vec3 {
+        let mut from = (0.0, self.start_position);
+        for &(time, position) in self.keyframes.iter() {
+            if progress < time {
+                let t = (progress - from.0) / (time - from.0);
+                return from.1.lerp(position, t.clamp(0.0, 1.0));
+            }
+            from = (time, position);
+        }
+        from.1
+    }
 }
 
 fn setup_game_turn_timer(
     mut action_timer: ResMut<ActionTimer>,
     mut commands: Commands,
-    log_query: Query<(Entity, &Transform), With<Log>>,
+    log_query: Query<(Entity, &Transform, &Log)>,
+    player_query: Query<(Entity, &Transform), With<Player>>,
+    pending_turn: Res<PendingTurn>,
+    game: Res<Game>,
 ) {
     info!(
         "GameState: GameTurnInProgress. Starting {GAME_MOVE_ANIMATION_DURATION}s game move timer."
@@ -1070,17 +1889,76 @@ fn setup_game_turn_timer(
     action_timer.0 = Timer::from_seconds(GAME_MOVE_ANIMATION_DURATION, TimerMode::Once);
     action_timer.0.reset();
 
+    let Some(turn) = pending_turn.0.as_ref() else {
+        return;
+    };
+
+    // Birds that move this turn fly over to their new tile
+    let bird_map = &game.current_level().bird_map;
+    for bird in game.board.birds.iter() {
+        let Some(moved) = turn
+            .board
+            .birds
+            .iter()
+            .find(|next| next.spawn == bird.spawn)
+        else {
+            continue;
+        };
+        if moved.pos == bird.pos {
+            continue;
+        }
+        if let Some(entity) = bird_map.get(&bird_tile(bird.pos)) {
+            commands.entity(*entity).insert(BirdFlight {
+                start_position: bird_tile(bird.pos).as_vec3(),
+                target_position: bird_tile(moved.pos).as_vec3(),
+            });
+        }
+    }
+
+    if let (Some(from), Ok((player_entity, _))) = (turn.pushed_from, player_query.single()) {
+        commands.entity(player_entity).insert(Conveyed {
+            start_position: player_translation(from, false),
+            target_position: player_translation(turn.board.player, false),
+        });
+    }
+
     // Add LogRoll component to all logs
-    for (log_entity, log_transform) in log_query.iter() {
+    for (log_entity, log_transform, log) in log_query.iter() {
+        let Some(rolled) = turn.rolled.iter().find(|rolled| rolled.id == log.id) else {
+            continue;
+        };
         let start_position = log_transform.translation;
-        let target_position = Vec3::new(
-            start_position.x,
-            start_position.y,
-            start_position.z - TILE_SIZE * 2.0,
-        );
+        let target_position = log_translation(rolled);
+        // Every tick takes the same time, the log holds still between its moves
+        let mut keyframes = Vec::new();
+        let mut position = start_position;
+        for step in turn.moves.iter().filter(|step| step.id == log.id) {
+            let tick = step.tick as f32 / turn.ticks as f32;
+            let tick_start = (step.tick - 1) as f32 / turn.ticks as f32;
+            keyframes.push((tick_start, position));
+            position = log_translation(&SimLog {
+                pos: step.pos,
+                ..*rolled
+            });
+            keyframes.push((tick, position));
+        }
+        let carrier = player_query
+            .single()
+            .ok()
+            .filter(|_| turn.carried_by == Some(log.id));
+        if let Some((player_entity, player_transform)) = carrier {
+            commands.entity(player_entity).insert(Riding {
+                log: log_entity,
+                offset: player_transform.translation - start_position,
+            });
+        }
+        let dir = rolled.dir.as_vec2();
         commands.entity(log_entity).insert(LogRoll {
             start_position,
             target_position,
+            roll_axis: Vec3::Y.cross(Vec3::new(dir.x, 0.0, dir.y)),
+            rest_rotation: log_rotation(rolled),
+            keyframes,
         });
     }
 }
@@ -1089,10 +1967,12 @@ fn process_game_turn(
     time: Res<Time>,
     mut action_timer: ResMut<ActionTimer>,
     player_action_tracker: Res<PlayerActionTracker>,
-    mut log_query: Query<(Entity, &mut Transform, &LogRoll), With<Log>>,
-    game: Res<Game>,
+    mut log_query: Query<(Entity, &mut Transform, &LogRoll, &Log)>,
+    mut player_query: Query<(Entity, &mut Transform, &Riding), (With<Player>, Without<Log>)>,
+    pending_turn: Res<PendingTurn>,
     mut commands: Commands,
     mut next_game_state: ResMut<NextState<GameState>>,
+    mut collision_event: EventWriter<CollisionEvent>,
 ) {
     action_timer.0.tick(time.delta());
 
@@ -1100,35 +1980,47 @@ fn process_game_turn(
     let progress = action_timer.0.fraction();
 
     // Smoothly interpolate log positions based on timer progress
-    for (log_entity, mut log_transform, log_roll) in log_query.iter_mut() {
-        // Lerp between start and target positions
-        log_transform.translation = log_roll
-            .start_position
-            .lerp(log_roll.target_position, progress);
-
-        // Check if log is out of bounds (only if we're close to completion)
-        if progress > 0.9 {
-            let board_pos = IVec3::new(
-                log_transform.translation.x as i32,
-                log_transform.translation.y as i32,
-                log_transform.translation.z as i32,
-            );
-
-            if !game.is_valid_board_pos(board_pos, &Direction::West) {
-                info!("Log went out of bounds! Despawning it.");
-                commands.entity(log_entity).despawn();
-            }
+    for (_log_entity, mut log_transform, log_roll, _log) in log_query.iter_mut() {
+        log_transform.translation = log_roll.position(progress);
+    }
+    // A ridden log carries the player along
+    if let Ok((_player_entity, mut player_transform, riding)) = player_query.single_mut() {
+        let log_translation = log_query
+            .get(riding.log)
+            .map(|(_, transform, _, _)| transform.translation);
+        if let Ok(log_translation) = log_translation {
+            player_transform.translation = log_translation + riding.offset;
         }
     }
 
     if action_timer.0.just_finished() {
+        let Some(turn) = pending_turn.0.as_ref() else {
+            return;
+        };
+
+        if let Ok((player_entity, mut player_transform, _riding)) = player_query.single_mut() {
+            player_transform.translation = player_translation(turn.board.player, true);
+            commands.entity(player_entity).remove::<Riding>();
+        }
+
         // Make sure all logs are exactly at their target positions and reset rotation
-        for (log_entity, mut log_transform, log_roll) in log_query.iter_mut() {
+        for (log_entity, mut log_transform, log_roll, log) in log_query.iter_mut() {
+            if !turn.board.logs.iter().any(|next| next.id == log.id) {
+                info!("Log went out of bounds! Despawning it.");
+                commands.entity(log_entity).despawn();
+                continue;
+            }
             log_transform.translation = log_roll.target_position;
-            log_transform.rotation = Quat::IDENTITY; // Reset rotation to 0
+            log_transform.rotation = log_roll.rest_rotation; // Reset the roll
             commands.entity(log_entity).remove::<LogRoll>(); // Remove the LogRoll component
         }
 
+        // The hit is normally picked up by `collision_detection_system` during the roll,
+        // this covers a hit where the boxes never touched or the player was carried
+        if turn.outcome.is_hit() {
+            collision_event.write(CollisionEvent::PlayerLog);
+        }
+
         info!("Game move animation finished. Log movement complete.");
 
         if player_action_tracker.is_jumping {
@@ -1141,61 +2033,219 @@ fn process_game_turn(
     }
 }
 
+fn knock_back_player(
+    time: Res<Time>,
+    mut player_query: Query<(Entity, &mut Transform, &mut Knockback), With<Player>>,
+    mut commands: Commands,
+) {
+    let Ok((player_entity, mut player_transform, mut knockback)) = player_query.single_mut() else {
+        return;
+    };
+    knockback.timer.tick(time.delta());
+    let progress = knockback.timer.fraction();
+    // A short hop back onto the ground
+    player_transform.translation = knockback
+        .start_position
+        .lerp(knockback.target_position, ease_in_out_cubic(progress));
+    player_transform.translation.y += 0.5 * (progress * PI).sin();
+
+    if knockback.timer.finished() {
+        player_transform.translation = knockback.target_position;
+        commands.entity(player_entity).remove::<Knockback>();
+    }
+}
+
+/// Blinks the player while the logs can't hurt them.
+fn flash_invulnerable_player(
+    time: Res<Time>,
+    game: Res<Game>,
+    mut player_query: Query<(&mut Visibility, Has<Knockback>), With<Player>>,
+) {
+    const FLASHES_PER_SECOND: f32 = 8.0;
+    let Ok((mut visibility, knocked_back)) = player_query.single_mut() else {
+        return;
+    };
+    let flashing = knocked_back || game.board.invulnerable > 0;
+    let hidden = flashing && (time.elapsed_secs() * FLASHES_PER_SECOND) as u32 % 2 == 1;
+    visibility.set_if_neq(if hidden {
+        Visibility::Hidden
+    } else {
+        Visibility::Inherited
+    });
+}
+
+/// A bird flying to another tile during the game turn.
+#[derive(Component)]
+pub struct BirdFlight {
+    pub start_position: Vec3,
+    pub target_position: Vec3,
+}
+
+fn fly_birds(
+    action_timer: Res<ActionTimer>,
+    mut bird_query: Query<(Entity, &mut Transform, &BirdFlight), With<Bird>>,
+    mut commands: Commands,
+) {
+    let progress = action_timer.0.fraction();
+    for (bird_entity, mut bird_transform, flight) in bird_query.iter_mut() {
+        bird_transform.translation = flight
+            .start_position
+            .lerp(flight.target_position, ease_in_out_cubic(progress));
+        bird_transform.translation.y += 0.5 * (progress * PI).sin();
+        if action_timer.0.finished() {
+            bird_transform.translation = flight.target_position;
+            commands.entity(bird_entity).remove::<BirdFlight>();
+        }
+    }
+}
+
+/// The player pushed a tile on by a conveyor during the game turn.
+#[derive(Component)]
+pub struct Conveyed {
+    pub start_position: Vec3,
+    pub target_position: Vec3,
+}
+
+fn convey_player(
+    action_timer: Res<ActionTimer>,
+    mut player_query: Query<(Entity, &mut Transform, &Conveyed), With<Player>>,
+    mut commands: Commands,
+) {
+    let Ok((player_entity, mut player_transform, conveyed)) = player_query.single_mut() else {
+        return;
+    };
+    let progress = action_timer.0.fraction();
+    player_transform.translation = conveyed
+        .start_position
+        .lerp(conveyed.target_position, progress);
+    if action_timer.0.finished() {
+        player_transform.translation = conveyed.target_position;
+        commands.entity(player_entity).remove::<Conveyed>();
+    }
+}
+
 // System to handle log rolling animation
-fn roll_logs(time: Res<Time>, mut log_query: Query<&mut Transform, (With<Log>, With<LogRoll>)>) {
-    for mut log_transform in log_query.iter_mut() {
+fn roll_logs(time: Res<Time>, mut log_query: Query<(&mut Transform, &LogRoll), With<Log>>) {
+    for (mut log_transform, log_roll) in log_query.iter_mut() {
+        // Logs stopped in a jam stay still
+        if log_roll.start_position == log_roll.target_position {
+            continue;
+        }
         // Calculate roll speed based on distance
-        let roll_speed = -PI; // Adjust as needed for rotation speed
+        let roll_speed = PI; // Adjust as needed for rotation speed
 
-        // Rotate around X axis (rolling forward/backward)
-        let rotation = Quat::from_rotation_x(time.delta_secs() * roll_speed);
+        // Rotate around the axis across the roll (rolling forward/backward)
+        let rotation = Quat::from_axis_angle(log_roll.roll_axis, time.delta_secs() * roll_speed);
         log_transform.rotate(rotation);
     }
 }
 
 #[derive(Event)]
-pub struct PlayerBirdRescueEvent;
+pub struct PlayerBirdRescueEvent {
+    pub kind: BirdKind,
+}
 
-fn increment_bevy(
-    _trigger: Trigger<PlayerBirdRescueEvent>,
-    mut game: ResMut<Game>,
-    mut commands: Commands,
-) {
+/// The carried birds were banked in the nest.
+#[derive(Event)]
+pub struct PlayerBevyDeliveryEvent {
+    pub birds: usize,
+    /// Every bird of the level is banked
+    pub won: bool,
+}
+
+fn increment_bevy(trigger: Trigger<PlayerBirdRescueEvent>, mut game: ResMut<Game>) {
     info!("birds left: {}", game.current_level().bird_map.len());
     game.bevy_count += 1;
     info!("bird count: {}", game.bevy_count);
-    if game.current_level().bird_map.is_empty() {
-        commands.trigger(GameEvent::Win);
+    let step = game.board.step;
+    let points = game.score.rescue(trigger.event().kind, step);
+    info!("rescue worth {points} points, combo x{}", game.score.combo);
+}
+
+fn bank_bevy(
+    trigger: Trigger<PlayerBevyDeliveryEvent>,
+    mut game: ResMut<Game>,
+    undo_history: Res<UndoHistory>,
+    mut commands: Commands,
+) {
+    info!("{} birds banked", trigger.event().birds);
+    game.score.deliver();
+    // The board moves on to the next turn once the logs have rolled
+    let step = game.board.step;
+    if trigger.event().won {
+        let scoring = game.current_level().scoring.clone();
+        let stars = game
+            .score
+            .finish_level(&scoring, step + 1, undo_history.undos);
+        info!(
+            "level beaten in {} turns for {} points",
+            step + 1,
+            game.score.level
+        );
+        game.record_stars(stars);
+        commands.trigger(GameEvent::Win { stars });
     }
 }
 
 fn player_check_for_bird(
-    player_query: Query<(Entity, &Transform), With<Player>>,
+    player_query: Query<(Entity, Option<&Children>), With<Player>>,
+    nest_query: Query<Entity, With<Nest>>,
     mut bird_query: Query<&mut Transform, (With<Bird>, Without<Player>)>,
     mut commands: Commands,
     mut game: ResMut<Game>,
-    player_action: Res<PlayerActionTracker>,
+    pending_turn: Res<PendingTurn>,
 ) {
-    if player_action.is_jumping {
+    let Some(turn) = pending_turn.0.as_ref() else {
+        return;
+    };
+    let Ok((player_ent, children)) = player_query.single() else {
+        return;
+    };
+    // The birds on the player, bottom first
+    let mut bevy: Vec<Entity> = children
+        .into_iter()
+        .flatten()
+        .copied()
+        .filter(|child| bird_query.contains(*child))
+        .collect();
+
+    if let Some(tile) = turn.rescued {
         info!("check for birds!");
-        match player_query.single() {
-            Ok((player_ent, tf)) => {
-                let mut pos = tf.translation.as_ivec3();
-                pos.y = BIRD_Y;
-                if let Some(entity) = game.current_level_mut().bird_map.remove(&pos) {
-                    commands.trigger(PlayerBirdRescueEvent);
-                    if let Ok(mut bird_tf) = bird_query.get_mut(entity) {
-                        let diff = (game.bevy_count + 1) as f32;
-                        bird_tf.translation = Vec3::ZERO;
-                        bird_tf.translation.y += 0.5 * diff;
-                        bird_tf.rotate_y(0.65 * diff);
-                    }
-                    info!("bird found adding to bevy!");
-                    commands.entity(player_ent).add_child(entity);
+        if let Some(entity) = game.current_level_mut().bird_map.remove(&bird_tile(tile)) {
+            let kind = game
+                .board
+                .birds
+                .iter()
+                .find(|bird| bird.pos == tile)
+                .map_or(BirdKind::Common, |bird| {
+                    game.current_level().birds[bird.spawn].kind
+                });
+            commands.trigger(PlayerBirdRescueEvent { kind });
+            if let Ok(mut bird_tf) = bird_query.get_mut(entity) {
+                stack_bird(&mut bird_tf, bevy.len());
+            }
+            info!("bird found adding to bevy!");
+            commands.entity(player_ent).add_child(entity);
+            bevy.push(entity);
+        }
+    }
+
+    if turn.delivered > 0 {
+        // Without a nest the banked birds stay on the player
+        if let Ok(nest) = nest_query.single() {
+            let banked = game.board.delivered.len();
+            let carried = &bevy[bevy.len().saturating_sub(turn.delivered)..];
+            for (idx, entity) in carried.iter().enumerate() {
+                if let Ok(mut bird_tf) = bird_query.get_mut(*entity) {
+                    stack_bird(&mut bird_tf, banked + idx);
                 }
+                commands.entity(nest).add_child(*entity);
             }
-            _ => (),
         }
+        commands.trigger(PlayerBevyDeliveryEvent {
+            birds: turn.delivered,
+            won: turn.outcome == Outcome::Won,
+        });
     }
 }
 
@@ -1203,6 +2253,7 @@ fn setup_player_jump_land_timer(
     mut action_timer: ResMut<ActionTimer>,
     mut commands: Commands,
     player_query: Query<(Entity, &Transform), With<Player>>,
+    game: Res<Game>,
 ) {
     info!(
         "GameState: PlayerFinishingJump. Starting {PLAYER_JUMP_LAND_ANIMATION_DURATION}s jump landing timer."
@@ -1213,7 +2264,9 @@ fn setup_player_jump_land_timer(
     // Set up player landing animation
     if let Ok((player_entity, player_transform)) = player_query.single() {
         let start_position = player_transform.translation;
-        let target_position = Vec3::new(start_position.x, start_position.y - 1.0, start_position.z);
+        // The turn is already applied, the board has the landing tile
+        let tile = game.board.player;
+        let target_position = player_translation(tile, game.board.riding.is_some());
 
         commands.entity(player_entity).insert(PlayerMove {
             start_position,
@@ -1238,9 +2291,15 @@ fn process_player_finishing_jump(
         // Use different easing for landing (more impactful)
         let eased_progress = easeoutbounce(progress).abs();
 
+        // The bounce is only up and down, a directional jump glides on to its landing tile
+        let glide = player_move
+            .start_position
+            .lerp(player_move.target_position, ease_in_out_cubic(progress));
         player_transform.translation = player_move
             .start_position
             .lerp(player_move.target_position, eased_progress);
+        player_transform.translation.x = glide.x;
+        player_transform.translation.z = glide.z;
     }
 
     if action_timer.0.just_finished() {
@@ -1384,6 +2443,29 @@ fn text_update_bird_count(mut query: Query<&mut TextSpan, With<BirdCountText>>,
     }
 }
 
+fn text_update_lives(mut query: Query<&mut TextSpan, With<LivesText>>, game: Res<Game>) {
+    for mut span in &mut query {
+        **span = format!("{}", game.board.lives);
+    }
+}
+
+fn text_update_score(mut query: Query<&mut TextSpan, With<ScoreText>>, game: Res<Game>) {
+    let combo = game.score.streak(game.board.step);
+    for mut span in &mut query {
+        **span = if combo > 1 {
+            format!("{} x{combo}", game.score.points())
+        } else {
+            format!("{}", game.score.points())
+        };
+    }
+}
+
+fn text_update_level(mut query: Query<&mut TextSpan, With<LevelText>>, game: Res<Game>) {
+    for mut span in &mut query {
+        **span = format!("{}/{}", game.current_level_index() + 1, game.levels.len());
+    }
+}
+
 fn text_update_game_message(
     trigger: Trigger<GameEvent>,
     mut query: Query<(&mut Text, &mut Visibility), With<GameMessage>>,
@@ -0,0 +1,162 @@
+//! Points for rescuing birds quickly and in streaks, and the stars they earn a level.
+
+use bevy::prelude::*;
+
+use crate::level::{BirdKind, LevelScoring};
+
+/// Most a rescue can be multiplied by a streak
+pub const MAX_COMBO: u32 = 4;
+/// Bonus for beating a level within its par
+pub const PAR_BONUS: u32 = 200;
+/// Extra bonus for every turn under par
+pub const UNDER_PAR_POINTS: u32 = 25;
+/// Taken off the level points for every undo
+pub const UNDO_PENALTY: u32 = 50;
+
+impl BirdKind {
+    pub fn points(&self) -> u32 {
+        match self {
+            BirdKind::Common => 100,
+            BirdKind::Golden => 300,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Reflect)]
+pub struct Score {
+    /// Points banked on the levels beaten before this one
+    pub total: u32,
+    /// Points scored on the current level
+    pub level: u32,
+    /// Points of each bird being carried, they count once the birds are in the nest
+    pub carried: Vec<u32>,
+    /// Rescues on back to back turns, each one multiplies its points by the streak
+    pub combo: u32,
+    /// Turn of the latest rescue
+    last_rescue: Option<u32>,
+}
+
+impl Score {
+    /// Scores a bird rescued on turn `step` and returns its points.
+    pub fn rescue(&mut self, kind: BirdKind, step: u32) -> u32 {
+        self.combo = if self.last_rescue.is_some_and(|last| last + 1 == step) {
+            (self.combo + 1).min(MAX_COMBO)
+        } else {
+            1
+        };
+        self.last_rescue = Some(step);
+        let points = kind.points() * self.combo;
+        self.carried.push(points);
+        points
+    }
+
+    /// Banks the points of the carried birds.
+    pub fn deliver(&mut self) {
+        self.level += self.carried.drain(..).sum::<u32>();
+    }
+
+    /// The last bird rescued was put down, its points go with it.
+    pub fn drop_bird(&mut self) {
+        self.carried.pop();
+    }
+
+    /// The carried birds scattered, their points are lost and so is the streak.
+    pub fn scatter(&mut self) {
+        self.carried.clear();
+        self.combo = 0;
+        self.last_rescue = None;
+    }
+
+    /// The streak that the rescue on turn `step` would carry on, 0 if it is broken.
+    pub fn streak(&self, step: u32) -> u32 {
+        if self.last_rescue.is_some_and(|last| last + 1 == step) {
+            self.combo
+        } else {
+            0
+        }
+    }
+
+    /// Adds the par bonus and the undo penalty once the level is beaten in `turns`
+    /// turns, and returns the stars the level points earn. Beating a level is always
+    /// worth a star.
+    pub fn finish_level(&mut self, scoring: &LevelScoring, turns: u32, undos: u32) -> u32 {
+        if let Some(par) = scoring.par.filter(|par| turns <= *par) {
+            self.level += PAR_BONUS + UNDER_PAR_POINTS * (par - turns);
+        }
+        self.level = self.level.saturating_sub(UNDO_PENALTY * undos);
+        let stars = scoring
+            .stars
+            .iter()
+            .filter(|points| self.level >= **points)
+            .count() as u32;
+        stars.max(1)
+    }
+
+    /// A score with nothing scored on the current level yet.
+    pub fn banked(total: u32) -> Self {
+        Self { total, ..default() }
+    }
+
+    /// Moves the level points to the total, for the next level.
+    pub fn bank(&mut self) {
+        *self = Score::banked(self.points());
+    }
+
+    pub fn points(&self) -> u32 {
+        self.total + self.level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_combo() {
+        let mut score = Score::default();
+        assert_eq!(score.rescue(BirdKind::Common, 3), 100);
+        assert_eq!(score.streak(4), 1);
+        assert_eq!(score.rescue(BirdKind::Common, 4), 200);
+        assert_eq!(score.rescue(BirdKind::Golden, 5), 900);
+        // A turn without a rescue breaks the streak
+        assert_eq!(score.streak(7), 0);
+        assert_eq!(score.rescue(BirdKind::Common, 7), 100);
+        assert_eq!(score.carried, vec![100, 200, 900, 100]);
+        score.drop_bird();
+        score.deliver();
+        assert_eq!((score.level, score.carried.len()), (1200, 0));
+
+        for step in 8..14 {
+            score.rescue(BirdKind::Common, step);
+        }
+        assert_eq!(score.combo, MAX_COMBO);
+
+        // Scattering loses the carried birds and the streak
+        score.scatter();
+        assert_eq!(score.points(), 1200);
+        assert_eq!(score.rescue(BirdKind::Common, 14), 100);
+    }
+
+    #[test]
+    fn test_finish_level() {
+        let scoring = LevelScoring {
+            par: Some(10),
+            stars: [300, 500, 700],
+        };
+        let mut score = Score {
+            total: 1000,
+            level: 300,
+            ..default()
+        };
+        // Two turns under par
+        assert_eq!(score.finish_level(&scoring, 8, 0), 2);
+        assert_eq!(score.level, 300 + PAR_BONUS + 2 * UNDER_PAR_POINTS);
+        score.bank();
+        assert_eq!((score.total, score.level), (1550, 0));
+
+        // Over par, and the undos cost more than the birds were worth
+        score.level = 100;
+        assert_eq!(score.finish_level(&scoring, 11, 3), 1);
+        assert_eq!(score.level, 0);
+    }
+}
@@ -0,0 +1,1549 @@
+//! The turn rules of the game, free of systems, timers and entities.
+//!
+//! [`Simulation::step`] takes the [`Board`] of the current turn and the player's
+//! [`Action`] and returns the [`Turn`]: the next board and what happened on the way.
+//! The ECS systems in `main.rs` only animate that result, so the rules can be unit
+//! tested and searched by a solver without running the app.
+
+use std::cmp::Reverse;
+
+use bevy::math::{IVec2, Vec2, Vec3Swizzles};
+
+use crate::{
+    Direction, Game,
+    level::{BirdBehavior, Level, SequenceState, TileKind},
+};
+
+/// Tiles covered by a log, unless its sequence sets a `length`
+pub const LOG_LENGTH: i32 = 4;
+/// Tiles a log rolls every turn, unless its sequence sets a `speed`
+pub const LOG_ROLL_TILES: i32 = 2;
+/// Hits the player can take on a level, unless the level sets its own `lives`
+pub const LIVES: u32 = 3;
+/// Turns after a hit during which the logs can't hurt the player
+pub const INVULNERABLE_TURNS: u32 = 3;
+/// Carried birds too heavy to jump over a tile with, banked birds weigh nothing
+pub const HEAVY_BEVY: usize = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    /// Walk one tile, `Direction::None` waits in place
+    Move(Direction),
+    /// Jump over the rolling logs, rescuing the bird at the top of the jump. A jump in
+    /// a direction clears one tile and lands on the next one, `Direction::None` jumps in
+    /// place.
+    Jump(Direction),
+    /// Put the last carried bird down on the player's tile, to lighten the bevy
+    Drop,
+}
+
+impl Action {
+    /// Everything the player can choose from the keyboard.
+    pub const PLAYER_CHOICES: [Action; 10] = [
+        Action::Move(Direction::North),
+        Action::Move(Direction::East),
+        Action::Move(Direction::South),
+        Action::Move(Direction::West),
+        Action::Jump(Direction::None),
+        Action::Jump(Direction::North),
+        Action::Jump(Direction::East),
+        Action::Jump(Direction::South),
+        Action::Jump(Direction::West),
+        Action::Drop,
+    ];
+
+    pub fn is_jump(&self) -> bool {
+        matches!(self, Action::Jump(_))
+    }
+}
+
+/// Tiles a directional jump travels, the tile in between is cleared
+pub const JUMP_TILES: i32 = 2;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SimLog {
+    /// Stable for the life of the log, used to find its entity
+    pub id: u32,
+    /// Index of the sequence that spawned the log
+    pub seq: usize,
+    /// The tile the log is centered on, an even length log has one more tile before it
+    pub pos: IVec2,
+    pub length: i32,
+    /// The tile it moves by when it rolls, the log lies across it
+    pub dir: IVec2,
+    /// Tiles rolled per turn
+    pub speed: i32,
+    /// Stopped against a rock or by knocking on another log. It blocks the player and
+    /// stays put until another log knocks it on.
+    pub stopped: bool,
+}
+
+impl SimLog {
+    /// The direction the log lies along, across its roll.
+    pub fn axis(&self) -> IVec2 {
+        IVec2::new(self.dir.y.abs(), self.dir.x.abs())
+    }
+
+    /// First tile covered by the log.
+    pub fn min(&self) -> IVec2 {
+        self.pos - self.axis() * (self.length / 2)
+    }
+
+    /// Last tile covered by the log.
+    pub fn max(&self) -> IVec2 {
+        self.min() + self.axis() * (self.length - 1)
+    }
+
+    /// Center on the board, halfway between the first and last covered tile.
+    pub fn center(&self) -> Vec2 {
+        (self.min() + self.max()).as_vec2() * 0.5
+    }
+
+    pub fn covers(&self, tile: IVec2) -> bool {
+        tile.cmpge(self.min()).all() && tile.cmple(self.max()).all()
+    }
+
+    /// `true` if the logs share a tile.
+    pub fn overlaps(&self, other: &SimLog) -> bool {
+        self.min().cmple(other.max()).all() && other.min().cmple(self.max()).all()
+    }
+
+    pub fn tiles(&self) -> impl Iterator<Item = IVec2> + '_ {
+        (0..self.length).map(|idx| self.min() + self.axis() * idx)
+    }
+
+    fn moved(&self, dir: IVec2) -> SimLog {
+        SimLog {
+            pos: self.pos + dir,
+            ..*self
+        }
+    }
+}
+
+/// What keeps a log rolling during a turn. A log that knocks on another hands it over.
+#[derive(Clone, Copy)]
+struct Momentum {
+    dir: IVec2,
+    speed: i32,
+}
+
+impl Momentum {
+    /// `true` if the log moves on `tick` of a roll that lasts `ticks`, the moves are
+    /// spread evenly over the roll.
+    fn moves_on(&self, tick: i32, ticks: i32) -> bool {
+        tick * self.speed / ticks > (tick - 1) * self.speed / ticks
+    }
+}
+
+/// A bird waiting to be rescued.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct SimBird {
+    /// Index of the bird in `Level.birds`
+    pub spawn: usize,
+    pub pos: IVec2,
+    /// Next of its hops, or the tile of its path it is on
+    pub leg: usize,
+}
+
+/// Everything that changes from one turn to the next.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Board {
+    pub step: u32,
+    pub player: IVec2,
+    /// Id of the log the player stands on, they roll along with it
+    pub riding: Option<u32>,
+    pub lives: u32,
+    /// Turns left before the player can be hit again
+    pub invulnerable: u32,
+    pub logs: Vec<SimLog>,
+    /// Birds still waiting to be rescued
+    pub birds: Vec<SimBird>,
+    /// Spawn indices of the birds the player carries, in the order they were rescued
+    pub carried: Vec<usize>,
+    /// Spawn indices of the birds banked in the nest
+    pub delivered: Vec<usize>,
+    /// Stuck in the mud, the player's next turn is spent waiting
+    pub stuck: bool,
+    /// One entry per sequence of the level
+    pub sequences: Vec<SequenceState>,
+    next_log_id: u32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Moved,
+    RescuedBird,
+    /// A log hit the player, who lost a life and was knocked back
+    Hit,
+    /// The player was hit with no lives left, or drowned
+    Died,
+    Won,
+}
+
+impl Outcome {
+    pub fn is_hit(&self) -> bool {
+        matches!(self, Outcome::Hit | Outcome::Died)
+    }
+}
+
+/// One tile of a log's roll. `tick` goes from 1 to `Turn.ticks`, the fastest log moves on
+/// every tick. A knocked log moves on the tick it was hit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LogMove {
+    pub id: u32,
+    pub tick: i32,
+    pub pos: IVec2,
+}
+
+/// A tile a log rolls over on one of the coming turns.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LogSweep {
+    pub turn: u32,
+    pub tile: IVec2,
+}
+
+#[derive(Clone, Debug)]
+pub struct Turn {
+    pub action: Action,
+    pub outcome: Outcome,
+    /// The board at the start of the next turn, its logs are already spawned
+    pub board: Board,
+    /// Every log at the end of its roll, including the ones that left the board
+    pub rolled: Vec<SimLog>,
+    /// Every tile rolled by the logs, in the order they happened
+    pub moves: Vec<LogMove>,
+    /// Ticks the roll took
+    pub ticks: i32,
+    /// Id of the log that carried the player through the roll
+    pub carried_by: Option<u32>,
+    /// The tile of the bird picked up this turn
+    pub rescued: Option<IVec2>,
+    /// Carried birds banked in the nest this turn
+    pub delivered: usize,
+    /// The bird put down this turn, on the tile it landed on
+    pub dropped: Option<SimBird>,
+    /// The carried birds knocked off the player by a hit, on the tiles they landed on
+    pub scattered: Vec<SimBird>,
+    /// Tile a conveyor pushed the player off once the logs had rolled
+    pub pushed_from: Option<IVec2>,
+}
+
+/// The rules of the current level of a [`Game`].
+pub struct Simulation<'a> {
+    pub board_size: IVec2,
+    pub start: IVec2,
+    pub lives: u32,
+    pub level: &'a Level,
+}
+
+impl<'a> Simulation<'a> {
+    pub fn new(game: &'a Game) -> Self {
+        Self::for_level(game, game.current_level())
+    }
+
+    /// The rules of any level on the board of `game`.
+    pub fn for_level(game: &Game, level: &'a Level) -> Self {
+        Self {
+            board_size: game.board_size_as_ivec2().as_ivec2(),
+            start: game.start,
+            lives: level.lives.unwrap_or(game.lives),
+            level,
+        }
+    }
+
+    /// The board when the level starts, with the logs of the first turn spawned.
+    pub fn start(&self) -> Board {
+        // Only the first of the birds sharing a tile can be rescued
+        let mut birds: Vec<SimBird> = Vec::new();
+        for (spawn, bird) in self.level.birds.iter().enumerate() {
+            if !birds.iter().any(|other| other.pos == bird.pos) {
+                birds.push(SimBird {
+                    spawn,
+                    pos: bird.pos,
+                    leg: 0,
+                });
+            }
+        }
+        let mut board = Board {
+            player: self.start,
+            lives: self.lives,
+            birds,
+            sequences: vec![SequenceState::default(); self.level.seq.len()],
+            ..Default::default()
+        };
+        self.spawn_logs(&mut board);
+        board
+    }
+
+    /// The board after `step` turns with the player waiting at the start. The logs don't
+    /// depend on the player, so these are the logs any game has on that turn.
+    pub fn board_at_step(&self, step: u32) -> Board {
+        let mut board = self.start();
+        while board.step < step {
+            board = self.step(&board, Action::Move(Direction::None)).board;
+        }
+        board
+    }
+
+    pub fn on_board(&self, pos: IVec2) -> bool {
+        pos.x >= 0 && pos.x < self.board_size.x && pos.y >= 0 && pos.y < self.board_size.y
+    }
+
+    /// `true` if any tile of `log` is on the board.
+    pub fn on_board_any(&self, log: &SimLog) -> bool {
+        log.tiles().any(|tile| self.on_board(tile))
+    }
+
+    pub fn is_rock(&self, tile: IVec2) -> bool {
+        self.level.rocks.contains(&tile)
+    }
+
+    pub fn tile_kind(&self, tile: IVec2) -> TileKind {
+        self.level.tiles.get(tile)
+    }
+
+    /// `true` if the player can walk onto `tile`. Water can only be walked onto along a
+    /// ridden log.
+    pub fn is_walkable(&self, board: &Board, tile: IVec2) -> bool {
+        self.on_board(tile)
+            && !self.is_rock(tile)
+            && self.tile_kind(tile) != TileKind::Water
+            && !board.logs.iter().any(|log| log.stopped && log.covers(tile))
+    }
+
+    pub fn is_valid_action(&self, board: &Board, action: Action) -> bool {
+        if board.stuck {
+            return action == Action::Move(Direction::None);
+        }
+        match action {
+            Action::Move(Direction::None) | Action::Jump(Direction::None) => true,
+            Action::Move(dir) => {
+                let tile = board.player + dir.value().xz();
+                self.is_walkable(board, tile)
+                    || self.ridden_log(board).is_some_and(|log| log.covers(tile))
+            }
+            // Jumps can land on top of any log
+            Action::Jump(dir) => {
+                let tile = board.player + dir.value().xz() * JUMP_TILES;
+                self.on_board(tile) && !self.is_rock(tile) && board.carried.len() < HEAVY_BEVY
+            }
+            Action::Drop => !board.carried.is_empty() && self.is_free_for_bird(board, board.player),
+        }
+    }
+
+    pub fn ridden_log<'b>(&self, board: &'b Board) -> Option<&'b SimLog> {
+        let id = board.riding?;
+        board.logs.iter().find(|log| log.id == id)
+    }
+
+    /// Plays one turn: the player acts, the logs roll and the next turn's logs spawn.
+    ///
+    /// An invalid move leaves the player where they are and an invalid jump goes straight
+    /// up, check [`Simulation::is_valid_action`] first to reject them instead. Winning
+    /// ends the turn right away, the logs don't roll.
+    ///
+    /// A player that lands a jump on a log rides it, rolling along with it until they
+    /// step or jump off.
+    ///
+    /// Rescued birds are carried until the player's move or jump ends on the level's
+    /// nest, the level is won once they are all banked there. On a level without a nest
+    /// they are banked right away. Carrying [`HEAVY_BEVY`] birds rules out jumping over a
+    /// tile, the player can drop them to lighten the load.
+    ///
+    /// A log rolling over the player, or carrying them off the board, costs a life. The
+    /// player is knocked a tile on along the log's roll and can't be hit again for
+    /// [`INVULNERABLE_TURNS`] turns. The birds they carry scatter onto the closest free
+    /// tiles.
+    ///
+    /// The tiles of the level act once the player has moved. Walking onto ice slides on
+    /// until the next tile can't be walked onto. Ending the turn on a mud tile the player
+    /// wasn't on gets them stuck, whatever they choose next turn they wait. A conveyor
+    /// pushes a player standing on it a tile on after the roll, and a player left on
+    /// water without a log to stand on drowns.
+    pub fn step(&self, board: &Board, action: Action) -> Turn {
+        let action = if board.stuck {
+            Action::Move(Direction::None)
+        } else {
+            action
+        };
+        let mut next = board.clone();
+        let mut rescued = None;
+        let mut carried_by = None;
+        match action {
+            Action::Move(dir) => {
+                if self.is_valid_action(board, action) {
+                    next.player += dir.value().xz();
+                }
+                // Walking along the log keeps the player on it
+                carried_by = self
+                    .ridden_log(board)
+                    .filter(|log| log.covers(next.player))
+                    .map(|log| log.id);
+                let dir = dir.value().xz();
+                while dir != IVec2::ZERO
+                    && carried_by.is_none()
+                    && self.tile_kind(next.player) == TileKind::Ice
+                    && self.is_walkable(board, next.player + dir)
+                {
+                    next.player += dir;
+                }
+            }
+            Action::Jump(dir) => {
+                let dir = if self.is_valid_action(board, action) {
+                    dir.value().xz()
+                } else {
+                    IVec2::ZERO
+                };
+                let apex = board.player + dir;
+                if let Some(idx) = next.birds.iter().position(|bird| bird.pos == apex) {
+                    let bird = next.birds.remove(idx);
+                    next.carried.push(bird.spawn);
+                    rescued = Some(bird.pos);
+                }
+                next.player += dir * JUMP_TILES;
+            }
+            Action::Drop => {
+                carried_by = self
+                    .ridden_log(board)
+                    .filter(|log| log.covers(next.player))
+                    .map(|log| log.id);
+            }
+        }
+        next.riding = None;
+
+        let mut delivered = 0;
+        if self.level.nest.is_none_or(|nest| nest == next.player) {
+            delivered = next.carried.len();
+            next.delivered.append(&mut next.carried);
+        }
+        if delivered > 0 && next.birds.is_empty() {
+            return Turn {
+                action,
+                outcome: Outcome::Won,
+                rolled: next.logs.clone(),
+                moves: Vec::new(),
+                ticks: 0,
+                carried_by: None,
+                board: next,
+                rescued,
+                delivered,
+                dropped: None,
+                scattered: Vec::new(),
+                pushed_from: None,
+            };
+        }
+
+        let (rolled, moves, ticks) = self.roll_logs(&board.logs);
+        // A jumping player is above the logs for the whole roll and a carried one on top
+        // of them. A log that moved covers its starting tiles and every tile it rolled to.
+        let hit_by = if action.is_jump() || carried_by.is_some() {
+            None
+        } else {
+            board.logs.iter().find(|log| {
+                let mut rolls = moves.iter().filter(|step| step.id == log.id).peekable();
+                rolls.peek().is_some()
+                    && (log.covers(next.player)
+                        || rolls.any(|step| {
+                            SimLog {
+                                pos: step.pos,
+                                ..**log
+                            }
+                            .covers(next.player)
+                        }))
+            })
+        };
+        let mut knockback = hit_by.map(|log| log.dir);
+
+        if let Some(id) = carried_by {
+            let start = board.logs.iter().find(|log| log.id == id);
+            let end = rolled.iter().find(|log| log.id == id);
+            if let (Some(start), Some(end)) = (start, end) {
+                next.player += end.pos - start.pos;
+            }
+            if !self.on_board(next.player) {
+                // Thrown off on the edge of the board
+                next.player = next.player.clamp(IVec2::ZERO, self.board_size - 1);
+                knockback = Some(IVec2::ZERO);
+            }
+        }
+
+        next.invulnerable = board.invulnerable.saturating_sub(1);
+        let hit = knockback.is_some() && board.invulnerable == 0;
+        if let Some(dir) = knockback.filter(|_| hit) {
+            next.lives = board.lives.saturating_sub(1);
+            next.invulnerable = INVULNERABLE_TURNS;
+            let tile = next.player + dir;
+            if self.on_board(tile)
+                && !self.is_rock(tile)
+                && self.tile_kind(tile) != TileKind::Water
+                && !rolled.iter().any(|log| log.covers(tile))
+            {
+                next.player = tile;
+            }
+        } else if action.is_jump() || carried_by.is_some() {
+            next.riding = rolled
+                .iter()
+                .find(|log| log.covers(next.player))
+                .map(|log| log.id);
+        }
+
+        next.logs = rolled
+            .iter()
+            .filter(|log| self.on_board_any(log))
+            .copied()
+            .collect();
+        // A jumping player is still in the air
+        let mut pushed_from = None;
+        if let TileKind::Conveyor(dir) = self.tile_kind(next.player) {
+            let tile = next.player + dir.value().xz();
+            if !hit && !action.is_jump() && next.riding.is_none() && self.is_walkable(&next, tile) {
+                pushed_from = Some(next.player);
+                next.player = tile;
+            }
+        }
+        let drowned = self.tile_kind(next.player) == TileKind::Water && next.riding.is_none();
+        if drowned {
+            next.lives = 0;
+        }
+        next.stuck = !board.stuck
+            && next.player != board.player
+            && next.riding.is_none()
+            && self.tile_kind(next.player) == TileKind::Mud;
+        self.move_birds(&mut next);
+        // The dropped bird lands once the others have moved, it stays with the player if
+        // one of them took the tile
+        let dropped = if action == Action::Drop && self.is_free_for_bird(&next, board.player) {
+            next.carried.pop().map(|spawn| {
+                let bird = SimBird {
+                    spawn,
+                    pos: board.player,
+                    leg: 0,
+                };
+                next.birds.push(bird);
+                bird
+            })
+        } else {
+            None
+        };
+        let scattered = if hit {
+            self.scatter_birds(&mut next)
+        } else {
+            Vec::new()
+        };
+        next.step += 1;
+        self.spawn_logs(&mut next);
+
+        let outcome = if drowned || (hit && next.lives == 0) {
+            Outcome::Died
+        } else if hit {
+            Outcome::Hit
+        } else if rescued.is_some() {
+            Outcome::RescuedBird
+        } else {
+            Outcome::Moved
+        };
+        Turn {
+            action,
+            outcome,
+            board: next,
+            rolled,
+            moves,
+            ticks,
+            carried_by,
+            rescued,
+            delivered,
+            dropped,
+            scattered,
+            pushed_from,
+        }
+    }
+
+    /// The logs that spawn at the end of this turn, whatever the player does. The logs
+    /// roll the same on every action, so these are the logs `step` spawns.
+    pub fn next_spawns(&self, board: &Board) -> Vec<SimLog> {
+        self.roll_turn(board)
+            .0
+            .logs
+            .into_iter()
+            .filter(|log| log.id >= board.next_log_id)
+            .collect()
+    }
+
+    /// Every tile the logs roll onto during the next `turns` turns, including
+    /// the logs spawned on the way. Turn 1 is the roll at the end of this turn, a tile is
+    /// listed once, on the first turn it is swept.
+    pub fn log_paths(&self, board: &Board, turns: u32) -> Vec<LogSweep> {
+        let mut sweeps: Vec<LogSweep> = Vec::new();
+        let mut board = board.clone();
+        for turn in 1..=turns {
+            let (next, rolled, moves) = self.roll_turn(&board);
+            let tiles = moves.iter().flat_map(|step| {
+                rolled
+                    .iter()
+                    .filter(|log| log.id == step.id)
+                    .flat_map(|log| {
+                        SimLog {
+                            pos: step.pos,
+                            ..*log
+                        }
+                        .tiles()
+                        .collect::<Vec<_>>()
+                    })
+            });
+            for tile in tiles.filter(|tile| self.on_board(*tile)) {
+                if !sweeps.iter().any(|sweep| sweep.tile == tile) {
+                    sweeps.push(LogSweep { turn, tile });
+                }
+            }
+            board = next;
+        }
+        sweeps
+    }
+
+    /// The logs of `board` a turn on: rolled, gone once off the board and with the next
+    /// turn's logs spawned. Returns that board, every log at the end of its roll and the
+    /// tiles they rolled.
+    fn roll_turn(&self, board: &Board) -> (Board, Vec<SimLog>, Vec<LogMove>) {
+        let (rolled, moves, _) = self.roll_logs(&board.logs);
+        let mut next = board.clone();
+        next.logs = rolled
+            .iter()
+            .filter(|log| self.on_board_any(log))
+            .copied()
+            .collect();
+        next.step += 1;
+        self.spawn_logs(&mut next);
+        (next, rolled, moves)
+    }
+
+    /// Moves the birds that act on turn `board.step`, in the order of `Level.birds`. A
+    /// bird whose tile is taken waits and tries again on its next move.
+    fn move_birds(&self, board: &mut Board) {
+        for idx in 0..board.birds.len() {
+            let bird = board.birds[idx];
+            let Some(spawn) = self.level.birds.get(bird.spawn) else {
+                continue;
+            };
+            let (target, legs) = match &spawn.behavior {
+                BirdBehavior::Still => continue,
+                BirdBehavior::Hop { every, moves } => {
+                    if *every == 0 || moves.is_empty() || !(board.step + 1).is_multiple_of(*every) {
+                        continue;
+                    }
+                    let dir = moves[bird.leg % moves.len()];
+                    (bird.pos + dir.value().xz(), moves.len())
+                }
+                BirdBehavior::Flee { range } => {
+                    let Some(tile) = self.flee_tile(board, bird.pos, *range) else {
+                        continue;
+                    };
+                    (tile, 1)
+                }
+                BirdBehavior::Circle { path } => {
+                    let leg = (bird.leg + 1) % (path.len() + 1);
+                    let tile = if leg == 0 { spawn.pos } else { path[leg - 1] };
+                    (tile, path.len() + 1)
+                }
+            };
+            if self.is_free_for_bird(board, target) {
+                board.birds[idx].pos = target;
+                board.birds[idx].leg = (bird.leg + 1) % legs;
+            }
+        }
+    }
+
+    /// Drops the carried birds on the free tiles closest to the player, the first one
+    /// rescued closest. Returns them on the tiles they landed on.
+    fn scatter_birds(&self, board: &mut Board) -> Vec<SimBird> {
+        let mut scattered = Vec::new();
+        for spawn in std::mem::take(&mut board.carried) {
+            let tile = (1..self.board_size.element_sum())
+                .flat_map(|distance| ring(board.player, distance))
+                .find(|tile| self.is_free_for_bird(board, *tile));
+            let Some(pos) = tile else {
+                // Nowhere to go on a board full of birds and rocks
+                board.carried.push(spawn);
+                continue;
+            };
+            let bird = SimBird { spawn, pos, leg: 0 };
+            board.birds.push(bird);
+            scattered.push(bird);
+        }
+        scattered
+    }
+
+    fn is_free_for_bird(&self, board: &Board, tile: IVec2) -> bool {
+        self.on_board(tile)
+            && !self.is_rock(tile)
+            && !board.birds.iter().any(|bird| bird.pos == tile)
+    }
+
+    /// The free tile next to `pos` furthest from the player, if the player is `range`
+    /// tiles away or closer.
+    fn flee_tile(&self, board: &Board, pos: IVec2, range: u32) -> Option<IVec2> {
+        let distance = |tile: IVec2| (tile - board.player).abs().element_sum();
+        if distance(pos) > range as i32 {
+            return None;
+        }
+        [
+            Direction::North,
+            Direction::East,
+            Direction::South,
+            Direction::West,
+        ]
+        .iter()
+        .map(|dir| pos + dir.value().xz())
+        .filter(|tile| distance(*tile) > distance(pos) && self.is_free_for_bird(board, *tile))
+        // The first of the furthest ones
+        .min_by_key(|tile| Reverse(distance(*tile)))
+    }
+
+    /// Rolls every log `speed` tiles, one tick at a time. On each tick the logs furthest
+    /// along their roll move first, so the ones behind find the tiles they left. Returns
+    /// the logs in the order of `logs`, the tiles they rolled and the number of ticks.
+    ///
+    /// A log running into another stops and hands its momentum over, the knocked log
+    /// moves on for the rest of the roll and can knock on the next one in turn. A rock
+    /// takes the momentum, and so does a log the chain already knocked: logs lying across
+    /// each other would otherwise hand it back and forth for ever. A log that already
+    /// moved this tick takes it too, no log moves twice in a tick. Logs that are off the
+    /// board block like any other, so no log rolls into one.
+    fn roll_logs(&self, logs: &[SimLog]) -> (Vec<SimLog>, Vec<LogMove>, i32) {
+        let mut rolled = logs.to_vec();
+        let mut momentum: Vec<Option<Momentum>> = logs
+            .iter()
+            .map(|log| {
+                (!log.stopped).then_some(Momentum {
+                    dir: log.dir,
+                    speed: log.speed,
+                })
+            })
+            .collect();
+        let ticks = momentum
+            .iter()
+            .flatten()
+            .map(|m| m.speed)
+            .max()
+            .unwrap_or(0);
+
+        let mut moves = Vec::new();
+        for tick in 1..=ticks {
+            let mut due: Vec<usize> = (0..rolled.len())
+                .filter(|&idx| momentum[idx].is_some_and(|m| m.moves_on(tick, ticks)))
+                .collect();
+            due.sort_by_key(|&idx| {
+                let dir = momentum[idx].map_or(IVec2::ZERO, |m| m.dir);
+                (-rolled[idx].pos.dot(dir), rolled[idx].id)
+            });
+            // A knocked log may still be due this tick, it only moves once
+            let mut moved = vec![false; rolled.len()];
+            for idx in due {
+                let Some(m) = momentum[idx] else {
+                    continue;
+                };
+                if moved[idx] {
+                    continue;
+                }
+                let mut idx = idx;
+                let mut knocked = vec![idx];
+                loop {
+                    let next = rolled[idx].moved(m.dir);
+                    if next.tiles().any(|tile| self.is_rock(tile)) {
+                        momentum[idx] = None;
+                        break;
+                    }
+                    let hit = (0..rolled.len())
+                        .find(|&other| other != idx && rolled[other].overlaps(&next));
+                    if let Some(other) = hit {
+                        momentum[idx] = None;
+                        if knocked.contains(&other) || moved[other] {
+                            break;
+                        }
+                        knocked.push(other);
+                        idx = other;
+                        continue;
+                    }
+                    rolled[idx] = next;
+                    momentum[idx] = Some(m);
+                    moved[idx] = true;
+                    moves.push(LogMove {
+                        id: next.id,
+                        tick,
+                        pos: next.pos,
+                    });
+                    break;
+                }
+            }
+        }
+
+        for (log, momentum) in rolled.iter_mut().zip(momentum) {
+            log.stopped = momentum.is_none();
+        }
+        (rolled, moves, ticks)
+    }
+
+    /// Spawns the logs of this step's sequences. A log that would land on another log is
+    /// left out, a jam already reaches the edge of the board.
+    fn spawn_logs(&self, board: &mut Board) {
+        for (seq_idx, seq) in self.level.seq.iter().enumerate() {
+            let state = &mut board.sequences[seq_idx];
+            if !seq.advance_state(state, board.step) {
+                continue;
+            }
+            let offset = seq.spawn_offset(state.index);
+            let log = SimLog {
+                id: board.next_log_id,
+                seq: seq_idx,
+                pos: seq.edge.spawn_tile(self.board_size, offset.x, offset.y),
+                length: seq.length as i32,
+                dir: seq.edge.roll_direction(),
+                speed: seq.speed as i32,
+                stopped: false,
+            };
+            if board.logs.iter().any(|other| other.overlaps(&log)) {
+                continue;
+            }
+            board.logs.push(log);
+            board.next_log_id += 1;
+        }
+    }
+}
+
+/// The tiles `distance` steps away from `center`.
+fn ring(center: IVec2, distance: i32) -> impl Iterator<Item = IVec2> {
+    (0..distance).flat_map(move |idx| {
+        let (a, b) = (distance - idx, idx);
+        [
+            IVec2::new(a, b),
+            IVec2::new(-b, a),
+            IVec2::new(-a, -b),
+            IVec2::new(b, -a),
+        ]
+        .map(|offset| center + offset)
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::level::{BirdKind, BirdSpawn, LogSequence, SpawnEdge, TileGrid};
+
+    /// A game with a single level, its sequences given as patterns with their
+    /// `spawn_offset_x_base` and its birds sitting still. Shared with the solver tests.
+    pub(crate) fn game(patterns: &[(&str, i32)], birds: &[(i32, i32)]) -> Game {
+        let level = Level {
+            seq: patterns
+                .iter()
+                .map(|(pattern, base)| LogSequence::from_pattern(pattern, 1, *base).unwrap())
+                .collect(),
+            birds: birds
+                .iter()
+                .map(|(x, y)| BirdSpawn {
+                    pos: IVec2::new(*x, *y),
+                    kind: BirdKind::Common,
+                    name: None,
+                    behavior: BirdBehavior::Still,
+                })
+                .collect(),
+            ..Default::default()
+        };
+        Game {
+            levels: vec![level],
+            ..Default::default()
+        }
+    }
+
+    fn wait() -> Action {
+        Action::Move(Direction::None)
+    }
+
+    #[test]
+    fn test_start_spawns_first_turn() {
+        let game = game(&[("X...", 6), (".X..", 2)], &[(3, 3)]);
+        let board = Simulation::new(&game).start();
+        assert_eq!(board.player, game.start);
+        assert_eq!(board.step, 0);
+        assert_eq!(board.logs.len(), 1);
+        assert_eq!(board.logs[0].pos, IVec2::new(6, 12));
+        assert_eq!(bird_tiles(&board), vec![IVec2::new(3, 3)]);
+    }
+
+    #[test]
+    fn test_moves_stay_on_board() {
+        let game = game(&[("....", 0)], &[(3, 3)]);
+        let sim = Simulation::new(&game);
+        let board = sim.start();
+        let south = Action::Move(Direction::South);
+        assert!(!sim.is_valid_action(&board, south));
+        assert_eq!(sim.step(&board, south).board.player, board.player);
+
+        let turn = sim.step(&board, Action::Move(Direction::North));
+        assert_eq!(turn.outcome, Outcome::Moved);
+        assert_eq!(turn.board.player, board.player + IVec2::Y);
+        assert_eq!(turn.board.step, 1);
+    }
+
+    #[test]
+    fn test_log_sweeps_two_rows() {
+        let game = game(&[("X...", 6)], &[(3, 3)]);
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        // Log covers columns 4 to 7 and rolls from row 12 to 10
+        for (player, outcome) in [
+            (IVec2::new(6, 11), Outcome::Hit),
+            (IVec2::new(4, 10), Outcome::Hit),
+            (IVec2::new(7, 12), Outcome::Hit),
+            (IVec2::new(6, 9), Outcome::Moved),
+            (IVec2::new(3, 11), Outcome::Moved),
+            (IVec2::new(8, 11), Outcome::Moved),
+        ] {
+            board.player = player;
+            assert_eq!(sim.step(&board, wait()).outcome, outcome, "{player}");
+        }
+
+        board.player = IVec2::new(6, 11);
+        assert_eq!(
+            sim.step(&board, Action::Jump(Direction::None)).outcome,
+            Outcome::Moved
+        );
+        // Walking into the row the log is in
+        board.player = IVec2::new(6, 9);
+        let north = Action::Move(Direction::North);
+        assert_eq!(sim.step(&board, north).outcome, Outcome::Hit);
+    }
+
+    #[test]
+    fn test_rescue_and_win() {
+        let game = game(&[("X...", 6)], &[(6, 0), (6, 1)]);
+        let sim = Simulation::new(&game);
+        let board = sim.start();
+
+        let turn = sim.step(&board, wait());
+        assert_eq!(turn.rescued, None);
+
+        let turn = sim.step(&board, Action::Jump(Direction::None));
+        assert_eq!(turn.outcome, Outcome::RescuedBird);
+        assert_eq!(turn.rescued, Some(IVec2::new(6, 0)));
+        assert_eq!(bird_tiles(&turn.board), vec![IVec2::new(6, 1)]);
+
+        let turn = sim.step(&turn.board, Action::Move(Direction::North));
+        let won = sim.step(&turn.board, Action::Jump(Direction::None));
+        assert_eq!(won.outcome, Outcome::Won);
+        assert!(won.board.birds.is_empty());
+        // The turn ends on the rescue, nothing rolls
+        assert_eq!(won.board.step, turn.board.step);
+        assert_eq!(won.board.logs, turn.board.logs);
+    }
+
+    #[test]
+    fn test_logs_leave_board() {
+        let game = game(&[("X.......", 2)], &[(9, 9)]);
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        let first = board.logs[0].id;
+        for _ in 0..6 {
+            board = sim.step(&board, wait()).board;
+        }
+        assert_eq!(board.logs.len(), 1);
+        assert_eq!(board.logs[0].pos, IVec2::new(2, 0));
+
+        let turn = sim.step(&board, wait());
+        assert_eq!(turn.rolled[0].pos, IVec2::new(2, -2));
+        assert!(turn.board.logs.is_empty());
+        // The pattern repeats on turn 8 with a new log
+        let board = sim.step(&turn.board, wait()).board;
+        assert_eq!(board.logs.len(), 1);
+        assert_ne!(board.logs[0].id, first);
+    }
+
+    #[test]
+    fn test_rocks_block_moves() {
+        let mut game = game(&[], &[(3, 3)]);
+        game.levels[0].rocks = vec![game.start + IVec2::Y];
+        let sim = Simulation::new(&game);
+        let board = sim.start();
+        let north = Action::Move(Direction::North);
+        assert!(!sim.is_valid_action(&board, north));
+        assert_eq!(sim.step(&board, north).board.player, board.player);
+        assert!(sim.is_valid_action(&board, Action::Move(Direction::East)));
+    }
+
+    #[test]
+    fn test_rock_stops_logs() {
+        // Logs cover columns 4 to 7, the rock stops them all above row 7
+        let mut game = game(&[("X.", 6)], &[(3, 3)]);
+        game.levels[0].rocks = vec![IVec2::new(5, 7)];
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        board.player = IVec2::new(6, 6);
+        // An odd number of turns, the last log to spawn was left off the board
+        for _ in 0..21 {
+            let turn = sim.step(&board, wait());
+            assert_eq!(turn.outcome, Outcome::Moved);
+            board = turn.board;
+        }
+        let mut rows: Vec<i32> = board.logs.iter().map(|log| log.pos.y).collect();
+        rows.sort();
+        assert_eq!(rows, vec![8, 9, 10, 11]);
+        assert!(board.logs.iter().all(|log| log.stopped));
+
+        // Stopped logs block the player like rocks do
+        board.player = IVec2::new(6, 7);
+        assert!(!sim.is_valid_action(&board, Action::Move(Direction::North)));
+    }
+
+    #[test]
+    fn test_stopping_log_sweeps_its_roll() {
+        let mut game = game(&[("X...", 6)], &[(3, 3)]);
+        game.levels[0].rocks = vec![IVec2::new(7, 10)];
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        // The log stops on row 11 instead of rolling to row 10
+        board.player = IVec2::new(6, 11);
+        let turn = sim.step(&board, wait());
+        assert_eq!(turn.outcome, Outcome::Hit);
+        assert_eq!(turn.rolled[0].pos, IVec2::new(6, 11));
+        assert!(turn.rolled[0].stopped);
+
+        board.player = IVec2::new(6, 10);
+        assert_eq!(sim.step(&board, wait()).outcome, Outcome::Moved);
+    }
+
+    fn log(id: u32, pos: (i32, i32), stopped: bool) -> SimLog {
+        SimLog {
+            id,
+            seq: 0,
+            pos: IVec2::new(pos.0, pos.1),
+            length: LOG_LENGTH,
+            dir: IVec2::NEG_Y,
+            speed: LOG_ROLL_TILES,
+            stopped,
+        }
+    }
+
+    #[test]
+    fn test_log_knocks_stopped_log() {
+        let game = game(&[], &[(0, 9)]);
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        board.logs = vec![log(0, (6, 7), false), log(1, (7, 5), true)];
+
+        let turn = sim.step(&board, wait());
+        assert_eq!(
+            turn.rolled,
+            vec![log(0, (6, 6), true), log(1, (7, 4), false)]
+        );
+        assert_eq!(
+            turn.moves,
+            vec![
+                LogMove {
+                    id: 0,
+                    tick: 1,
+                    pos: IVec2::new(6, 6)
+                },
+                LogMove {
+                    id: 1,
+                    tick: 2,
+                    pos: IVec2::new(7, 4)
+                },
+            ]
+        );
+        // The knocked log rolls on, the one that hit it stays
+        let turn = sim.step(&turn.board, wait());
+        assert_eq!(
+            turn.rolled,
+            vec![log(0, (6, 6), true), log(1, (7, 2), false)]
+        );
+    }
+
+    #[test]
+    fn test_knocks_cascade() {
+        let mut game = game(&[], &[(0, 9)]);
+        game.levels[0].rocks = vec![IVec2::new(3, 1)];
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        board.logs = vec![
+            log(0, (6, 6), false),
+            log(1, (6, 5), true),
+            log(2, (5, 3), true),
+            log(3, (3, 2), true),
+        ];
+        board.player = IVec2::new(7, 4);
+
+        let turn = sim.step(&board, wait());
+        // 0 hits 1 straight away, 1 rolls a tile and hits 2, 2 hits 3 against the rock
+        assert_eq!(
+            turn.rolled,
+            vec![
+                log(0, (6, 6), true),
+                log(1, (6, 4), true),
+                log(2, (5, 3), true),
+                log(3, (3, 2), true),
+            ]
+        );
+        assert_eq!(turn.outcome, Outcome::Hit);
+        let ticks: Vec<(u32, i32)> = turn.moves.iter().map(|m| (m.id, m.tick)).collect();
+        assert_eq!(ticks, vec![(1, 1)]);
+    }
+
+    #[test]
+    fn test_crossed_logs_stop() {
+        let game = game(&[], &[(0, 9)]);
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        let crossing = SimLog {
+            dir: IVec2::NEG_X,
+            ..log(1, (4, 5), true)
+        };
+        board.logs = vec![log(0, (5, 5), false), crossing];
+        board.player = IVec2::new(9, 9);
+
+        // 0 knocks 1 down onto 0 again, the chain ends there instead of going round
+        let turn = sim.step(&board, wait());
+        assert_eq!(turn.rolled, vec![log(0, (5, 5), true), crossing]);
+        assert!(turn.moves.is_empty());
+    }
+
+    #[test]
+    fn test_knock_across_moves_once_per_tick() {
+        let game = game(&[], &[(0, 9)]);
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        let north = SimLog {
+            dir: IVec2::Y,
+            ..log(0, (5, 2), false)
+        };
+        let west = SimLog {
+            dir: IVec2::NEG_X,
+            ..log(1, (4, 5), false)
+        };
+        board.logs = vec![north, west];
+        board.player = IVec2::new(9, 9);
+
+        // 0 knocks 1 north on the first tick, 1 is still due but doesn't move again
+        let turn = sim.step(&board, wait());
+        assert_eq!(
+            turn.rolled,
+            vec![
+                SimLog {
+                    stopped: true,
+                    ..north
+                },
+                SimLog {
+                    pos: IVec2::new(4, 7),
+                    ..west
+                },
+            ]
+        );
+        let ticks: Vec<(u32, i32)> = turn.moves.iter().map(|m| (m.id, m.tick)).collect();
+        assert_eq!(ticks, vec![(1, 1), (1, 2)]);
+    }
+
+    #[test]
+    fn test_sequence_movement() {
+        let mut game = game(&[("X.", 5), ("X.", 9)], &[(0, 9)]);
+        let sequences = &mut game.levels[0].seq;
+        sequences[0].edge = SpawnEdge::West;
+        sequences[0].speed = 3;
+        sequences[0].length = 3;
+        sequences[1].edge = SpawnEdge::South;
+        sequences[1].speed = 1;
+        let sim = Simulation::new(&game);
+        let board = sim.start();
+        assert_eq!(board.logs[0].pos, IVec2::new(-1, 5));
+        assert_eq!(
+            (board.logs[0].min(), board.logs[0].max()),
+            (IVec2::new(-1, 4), IVec2::new(-1, 6))
+        );
+        assert_eq!(board.logs[1].pos, IVec2::new(9, -1));
+
+        let turn = sim.step(&board, wait());
+        assert_eq!(turn.ticks, 3);
+        assert_eq!(turn.rolled[0].pos, IVec2::new(2, 5));
+        assert_eq!(turn.rolled[1].pos, IVec2::new(9, 0));
+        // The slow log moves once, at the end of the roll
+        let slow: Vec<i32> = turn
+            .moves
+            .iter()
+            .filter(|m| m.id == 1)
+            .map(|m| m.tick)
+            .collect();
+        assert_eq!(slow, vec![3]);
+
+        // The fast log covers rows 4 to 6 as it rolls across the board
+        let mut board = board;
+        board.player = IVec2::new(1, 6);
+        assert_eq!(sim.step(&board, wait()).outcome, Outcome::Hit);
+        board.player = IVec2::new(3, 6);
+        assert_eq!(sim.step(&board, wait()).outcome, Outcome::Moved);
+
+        // Leaving through the far side
+        let mut board = sim.board_at_step(4);
+        assert!(board.logs.iter().any(|log| log.pos == IVec2::new(11, 5)));
+        board = sim.step(&board, wait()).board;
+        assert!(
+            board
+                .logs
+                .iter()
+                .all(|log| log.dir != IVec2::X || log.pos.x < 11)
+        );
+    }
+
+    #[test]
+    fn test_directional_jump() {
+        let mut game = game(&[("X...", 6)], &[(6, 10), (3, 3)]);
+        game.levels[0].rocks = vec![IVec2::new(6, 8)];
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        // Over the rock and under the log rolling from row 12 to 10
+        board.player = IVec2::new(6, 7);
+        let turn = sim.step(&board, Action::Jump(Direction::North));
+        assert_eq!(turn.outcome, Outcome::Moved);
+        assert_eq!(turn.board.player, IVec2::new(6, 9));
+
+        // The bird is caught over the cleared tile
+        board.player = IVec2::new(6, 9);
+        let turn = sim.step(&board, Action::Jump(Direction::North));
+        assert_eq!(turn.rescued, Some(IVec2::new(6, 10)));
+        assert_eq!(turn.board.player, IVec2::new(6, 11));
+
+        // Not onto a rock or off the board
+        board.player = IVec2::new(6, 10);
+        assert!(!sim.is_valid_action(&board, Action::Jump(Direction::North)));
+        board.player = IVec2::new(6, 6);
+        assert!(!sim.is_valid_action(&board, Action::Jump(Direction::North)));
+        board.player = IVec2::new(1, 6);
+        assert!(!sim.is_valid_action(&board, Action::Jump(Direction::West)));
+        let turn = sim.step(&board, Action::Jump(Direction::West));
+        assert_eq!(turn.board.player, board.player);
+    }
+
+    #[test]
+    fn test_ride_log() {
+        let game = game(&[], &[(6, 1), (0, 9)]);
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        board.logs = vec![log(0, (6, 7), false)];
+        board.player = IVec2::new(6, 3);
+
+        // The log rolls under the landing tile
+        let turn = sim.step(&board, Action::Jump(Direction::North));
+        assert_eq!(turn.outcome, Outcome::Moved);
+        assert_eq!(turn.board.player, IVec2::new(6, 5));
+        assert_eq!(turn.board.riding, Some(0));
+
+        // Carried along, a step along the log keeps the player on it
+        let turn = sim.step(&turn.board, Action::Move(Direction::East));
+        assert_eq!(turn.outcome, Outcome::Moved);
+        assert_eq!(turn.carried_by, Some(0));
+        assert_eq!(turn.board.player, IVec2::new(7, 3));
+
+        // Stepping off its end
+        let off = sim.step(&turn.board, Action::Move(Direction::East));
+        assert_eq!(off.outcome, Outcome::Moved);
+        assert_eq!(
+            (off.board.player, off.board.riding),
+            (IVec2::new(8, 3), None)
+        );
+
+        // The bird is caught over the ridden tile
+        let turn = sim.step(&turn.board, Action::Move(Direction::West));
+        assert_eq!(turn.board.player, IVec2::new(6, 1));
+        let rescue = sim.step(&turn.board, Action::Jump(Direction::None));
+        assert_eq!(rescue.rescued, Some(IVec2::new(6, 1)));
+        assert_eq!(rescue.board.riding, None);
+
+        // Riding the log off the board throws the player off on the edge
+        let thrown = sim.step(&turn.board, wait());
+        assert_eq!(thrown.outcome, Outcome::Hit);
+        assert_eq!(
+            (thrown.board.player, thrown.board.riding),
+            (IVec2::new(6, 0), None)
+        );
+    }
+
+    #[test]
+    fn test_lives() {
+        let game = game(&[], &[(0, 9)]);
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        assert_eq!(board.lives, 3);
+        board.logs = vec![log(0, (6, 7), false)];
+        board.player = IVec2::new(6, 5);
+
+        // Knocked on ahead of the log
+        let turn = sim.step(&board, wait());
+        assert_eq!(turn.outcome, Outcome::Hit);
+        assert_eq!(turn.board.player, IVec2::new(6, 4));
+        assert_eq!(
+            (turn.board.lives, turn.board.invulnerable),
+            (2, INVULNERABLE_TURNS)
+        );
+
+        // The log rolls over the player again without hurting them
+        let turn = sim.step(&turn.board, wait());
+        assert_eq!(turn.outcome, Outcome::Moved);
+        assert_eq!((turn.board.lives, turn.board.invulnerable), (2, 2));
+
+        board.lives = 1;
+        let turn = sim.step(&board, wait());
+        assert_eq!(turn.outcome, Outcome::Died);
+        assert_eq!(turn.board.lives, 0);
+
+        let mut game = game;
+        game.levels[0].lives = Some(1);
+        assert_eq!(Simulation::new(&game).start().lives, 1);
+    }
+
+    #[test]
+    fn test_nest() {
+        let mut game = game(&[], &[(6, 2), (9, 4)]);
+        game.levels[0].nest = Some(IVec2::new(3, 0));
+        let sim = Simulation::new(&game);
+        let board = sim.start();
+        let to_nest = |board: &Board| {
+            let mut board = board.clone();
+            board.player = IVec2::new(3, 1);
+            sim.step(&board, Action::Move(Direction::South))
+        };
+
+        let turn = sim.step(&board, Action::Move(Direction::North));
+        let turn = sim.step(&turn.board, Action::Jump(Direction::North));
+        assert_eq!(turn.outcome, Outcome::RescuedBird);
+        assert_eq!((turn.board.carried.clone(), turn.delivered), (vec![0], 0));
+
+        let turn = to_nest(&turn.board);
+        assert_eq!((turn.outcome, turn.delivered), (Outcome::Moved, 1));
+        assert_eq!(turn.board.delivered, vec![0]);
+        assert!(turn.board.carried.is_empty());
+
+        // The last bird only wins the level once it is in the nest too
+        let mut board = turn.board;
+        board.player = IVec2::new(9, 4);
+        let rescue = sim.step(&board, Action::Jump(Direction::None));
+        assert_eq!(rescue.outcome, Outcome::RescuedBird);
+        let won = to_nest(&rescue.board);
+        assert_eq!((won.outcome, won.delivered), (Outcome::Won, 1));
+
+        // A hit scatters the carried birds around the tile the player is knocked to
+        let mut board = sim.start();
+        board.birds.clear();
+        board.carried = vec![0, 1];
+        board.logs = vec![log(0, (6, 7), false)];
+        board.player = IVec2::new(6, 5);
+        let turn = sim.step(&board, wait());
+        assert_eq!(turn.outcome, Outcome::Hit);
+        assert_eq!(turn.board.player, IVec2::new(6, 4));
+        assert!(turn.board.carried.is_empty());
+        assert_eq!(
+            bird_tiles(&turn.board),
+            vec![IVec2::new(7, 4), IVec2::new(6, 5)]
+        );
+        assert_eq!(turn.scattered, turn.board.birds);
+    }
+
+    #[test]
+    fn test_heavy_bevy() {
+        let mut game = game(&[], &[(0, 9), (1, 9), (2, 9), (3, 9), (6, 2)]);
+        game.levels[0].nest = Some(IVec2::new(0, 0));
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        let north = Action::Jump(Direction::North);
+        assert!(!sim.is_valid_action(&board, Action::Drop));
+
+        board.birds.retain(|bird| bird.spawn == 4);
+        board.carried = vec![0, 1, 2, 3];
+        assert!(!sim.is_valid_action(&board, north));
+        assert!(sim.is_valid_action(&board, Action::Jump(Direction::None)));
+
+        // Putting the last bird down lightens the bevy enough to jump over a tile
+        let turn = sim.step(&board, Action::Drop);
+        assert_eq!(
+            turn.dropped,
+            Some(SimBird {
+                spawn: 3,
+                pos: IVec2::new(6, 0),
+                leg: 0
+            })
+        );
+        assert_eq!(turn.board.carried, vec![0, 1, 2]);
+        assert_eq!(
+            bird_tiles(&turn.board),
+            vec![IVec2::new(6, 2), IVec2::new(6, 0)]
+        );
+        assert!(sim.is_valid_action(&turn.board, north));
+        // Not onto another bird
+        assert!(!sim.is_valid_action(&turn.board, Action::Drop));
+    }
+
+    #[test]
+    fn test_tiles() {
+        let mut game = game(&[], &[(0, 11)]);
+        let level = &mut game.levels[0];
+        level.tiles = TileGrid::try_from(vec![
+            ".......m~".to_string(),
+            "......N".to_string(),
+            "..iii".to_string(),
+        ])
+        .unwrap();
+        level.rocks = vec![IVec2::new(5, 2)];
+        let sim = Simulation::new(&game);
+        let start = sim.start();
+        let east = Action::Move(Direction::East);
+        let north = Action::Move(Direction::North);
+
+        // Stuck in the mud for a turn
+        let turn = sim.step(&start, east);
+        assert!(turn.board.stuck);
+        assert!(!sim.is_valid_action(&turn.board, north));
+        let turn = sim.step(&turn.board, north);
+        assert_eq!(
+            (turn.board.player, turn.board.stuck),
+            (IVec2::new(7, 0), false)
+        );
+        assert!(sim.is_valid_action(&turn.board, north));
+
+        // Water can't be walked onto, and drowns a player who jumps in
+        assert!(!sim.is_valid_action(&turn.board, east));
+        let turn = sim.step(&start, Action::Jump(Direction::East));
+        assert_eq!((turn.outcome, turn.board.lives), (Outcome::Died, 0));
+
+        // The conveyor pushes on once the player stands on it
+        let turn = sim.step(&start, north);
+        assert_eq!(turn.pushed_from, Some(IVec2::new(6, 1)));
+        assert_eq!(turn.board.player, IVec2::new(6, 2));
+
+        // Sliding over the ice up to the rock
+        let mut board = start.clone();
+        board.player = IVec2::new(1, 2);
+        assert_eq!(sim.step(&board, east).board.player, IVec2::new(4, 2));
+        // and off it onto the ground
+        board.player = IVec2::new(4, 1);
+        assert_eq!(sim.step(&board, north).board.player, IVec2::new(4, 3));
+    }
+
+    fn bird_tiles(board: &Board) -> Vec<IVec2> {
+        board.birds.iter().map(|bird| bird.pos).collect()
+    }
+
+    #[test]
+    fn test_bird_behaviors() {
+        let mut game = game(&[], &[(3, 3), (8, 8), (5, 5), (0, 9)]);
+        let birds = &mut game.levels[0].birds;
+        birds[0].behavior = BirdBehavior::Hop {
+            every: 2,
+            moves: vec![Direction::North, Direction::South],
+        };
+        birds[1].behavior = BirdBehavior::Circle {
+            path: vec![IVec2::new(9, 8), IVec2::new(9, 9), IVec2::new(8, 9)],
+        };
+        birds[2].behavior = BirdBehavior::Flee { range: 2 };
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        board.player = IVec2::new(5, 2);
+
+        // The fleeing bird waits until the player comes close, then hops straight away
+        let mut tiles = Vec::new();
+        for action in [wait(), Action::Move(Direction::North), wait(), wait()] {
+            board = sim.step(&board, action).board;
+            tiles.push(bird_tiles(&board));
+        }
+        let tile = |x, y| IVec2::new(x, y);
+        assert_eq!(
+            tiles,
+            vec![
+                vec![tile(3, 3), tile(9, 8), tile(5, 5), tile(0, 9)],
+                vec![tile(3, 4), tile(9, 9), tile(5, 6), tile(0, 9)],
+                vec![tile(3, 4), tile(8, 9), tile(5, 6), tile(0, 9)],
+                vec![tile(3, 3), tile(8, 8), tile(5, 6), tile(0, 9)],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_board_at_step() {
+        let game = game(&[("X.+1^1XX X+1...", 2)], &[(9, 9)]);
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        for _ in 0..11 {
+            board = sim.step(&board, Action::Jump(Direction::None)).board;
+        }
+        assert_eq!(sim.board_at_step(11).logs, board.logs);
+    }
+
+    #[test]
+    fn test_next_spawns() {
+        let game = game(&[("X.+1^1XX X+1...", 2), ("X.X^1X .X.X", 8)], &[(9, 9)]);
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        let actions = [
+            wait(),
+            Action::Move(Direction::North),
+            Action::Jump(Direction::None),
+        ];
+        let mut spawned = 0;
+        for turn in 0..24 {
+            let upcoming = sim.next_spawns(&board);
+            let next = sim.step(&board, actions[turn % actions.len()]).board;
+            let new_logs: Vec<SimLog> = next
+                .logs
+                .iter()
+                .filter(|log| !board.logs.iter().any(|old| old.id == log.id))
+                .copied()
+                .collect();
+            assert_eq!(upcoming, new_logs, "turn {turn}");
+            spawned += new_logs.len();
+            board = next;
+        }
+        assert!(spawned > 0);
+    }
+
+    #[test]
+    fn test_log_paths() {
+        // A log rolling down column 6 to 9 two tiles a turn, spawned every other turn
+        let game = game(&[("X.", 7)], &[(0, 11)]);
+        let sim = Simulation::new(&game);
+        let board = sim.start();
+        let paths = sim.log_paths(&board, 3);
+        let log = board.logs[0];
+        let turns_over = |tile: IVec2| {
+            paths
+                .iter()
+                .find(|sweep| sweep.tile == tile)
+                .map(|sweep| sweep.turn)
+        };
+        // It rolls over the two rows in front of it on each turn
+        assert_eq!(turns_over(log.min() - IVec2::Y), Some(1));
+        assert_eq!(turns_over(log.max() - IVec2::Y * 2), Some(1));
+        assert_eq!(turns_over(log.min() - IVec2::Y * 3), Some(2));
+        assert_eq!(turns_over(log.min() - IVec2::Y * 6), Some(3));
+        assert_eq!(turns_over(log.min() - IVec2::Y * 7), None);
+        assert_eq!(turns_over(IVec2::new(0, 5)), None);
+
+        // The logs end up where the turns take them
+        let mut played = board;
+        for _ in 0..3 {
+            played = sim.step(&played, wait()).board;
+        }
+        for log in played.logs.iter() {
+            assert!(
+                log.tiles()
+                    .filter(|tile| sim.on_board(*tile))
+                    .all(|tile| turns_over(tile).is_some()),
+                "{log:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_many_turns() {
+        let game = game(&[("X.+1^1XX X+1...", 2), ("X.X^1X .X.X", 10)], &[(9, 9)]);
+        let sim = Simulation::new(&game);
+        let mut board = sim.start();
+        for _ in 0..10_000 {
+            board = sim.step(&board, wait()).board;
+            assert!(board.logs.len() <= 14);
+        }
+        assert_eq!(board.step, 10_000);
+    }
+}
@@ -0,0 +1,249 @@
+//! Finds the shortest way to beat a level by searching every turn of the [`Simulation`].
+
+use std::collections::VecDeque;
+
+use bevy::{math::IVec2, platform::collections::HashSet};
+
+use crate::{
+    level::SequenceState,
+    simulation::{Action, Board, HEAVY_BEVY, Outcome, SimBird, Simulation},
+};
+
+/// One of the shortest action sequences that rescues every bird.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Solution {
+    pub actions: Vec<Action>,
+}
+
+impl Solution {
+    /// Minimum number of turns needed to beat the level.
+    pub fn turns(&self) -> usize {
+        self.actions.len()
+    }
+}
+
+/// What makes two boards play out the same from here on. The logs, sequences and hopping
+/// birds only depend on the step through their periods, so the step is kept modulo
+/// `cycle`.
+///
+/// The lives are left out: the search never lets the player be hit, so every board it
+/// keeps has the lives the level starts with.
+#[derive(PartialEq, Eq, Hash)]
+struct StateKey {
+    player: IVec2,
+    /// Sequence and tile of the ridden log, log ids differ between equal boards
+    riding: Option<(usize, IVec2)>,
+    invulnerable: u32,
+    birds: Vec<SimBird>,
+    carried: Vec<usize>,
+    stuck: bool,
+    step: u32,
+    logs: Vec<(usize, IVec2, i32, bool)>,
+    sequences: Vec<SequenceState>,
+}
+
+impl StateKey {
+    fn new(board: &Board, cycle: u32) -> Self {
+        let mut logs: Vec<(usize, IVec2, i32, bool)> = board
+            .logs
+            .iter()
+            .map(|log| (log.seq, log.pos, log.length, log.stopped))
+            .collect();
+        logs.sort_by_key(|(seq, pos, length, stopped)| (*seq, pos.x, pos.y, *length, *stopped));
+        let riding = board
+            .logs
+            .iter()
+            .find(|log| Some(log.id) == board.riding)
+            .map(|log| (log.seq, log.pos));
+        Self {
+            player: board.player,
+            riding,
+            invulnerable: board.invulnerable,
+            birds: board.birds.clone(),
+            carried: board.carried.clone(),
+            stuck: board.stuck,
+            step: board.step % cycle,
+            logs,
+            sequences: board.sequences.clone(),
+        }
+    }
+}
+
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// Breadth first search from the start of the level. Returns `None` if the birds
+/// can't all be rescued, or if there are none: a level is only won by banking one.
+///
+/// Birds are only put down while the bevy is too heavy to jump, dropping one sooner
+/// can't open up a way the run doesn't already have.
+///
+/// The search always ends, the patterns repeat so the reachable boards are finite.
+pub fn solve(sim: &Simulation) -> Option<Solution> {
+    let start = sim.start();
+    if start.birds.is_empty() {
+        return None;
+    }
+    let cycle = sim
+        .level
+        .seq
+        .iter()
+        .map(|seq| seq.period().max(1) as u32)
+        .chain(sim.level.birds.iter().map(|bird| bird.behavior.period()))
+        .fold(1, |cycle, period| cycle / gcd(cycle, period) * period);
+
+    // Every visited board's parent node and the action that led to it
+    let mut nodes: Vec<(Option<usize>, Action)> = Vec::new();
+    let mut seen = HashSet::new();
+    seen.insert(StateKey::new(&start, cycle));
+    let mut queue = VecDeque::from([(start, None)]);
+
+    while let Some((board, node)) = queue.pop_front() {
+        for action in Action::PLAYER_CHOICES {
+            // A bird is only worth putting down to jump again, the run carries the others
+            let light = board.carried.len() < HEAVY_BEVY;
+            if (action == Action::Drop && light) || !sim.is_valid_action(&board, action) {
+                continue;
+            }
+            let turn = sim.step(&board, action);
+            match turn.outcome {
+                // A clean run, lives only forgive the player's mistakes
+                Outcome::Hit | Outcome::Died => continue,
+                Outcome::Won => {
+                    let mut actions = vec![action];
+                    let mut parent = node;
+                    while let Some(idx) = parent {
+                        let (grand_parent, action) = nodes[idx];
+                        actions.push(action);
+                        parent = grand_parent;
+                    }
+                    actions.reverse();
+                    return Some(Solution { actions });
+                }
+                Outcome::Moved | Outcome::RescuedBird => {}
+            }
+            if seen.insert(StateKey::new(&turn.board, cycle)) {
+                nodes.push((node, action));
+                queue.push_back((turn.board, Some(nodes.len() - 1)));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        Direction, Game,
+        level::{BirdBehavior, Level},
+        simulation::tests::game,
+    };
+
+    /// Plays `solution` and checks it wins on the last action.
+    fn replay(sim: &Simulation, solution: &Solution) {
+        let mut board = sim.start();
+        for (idx, action) in solution.actions.iter().enumerate() {
+            let turn = sim.step(&board, *action);
+            assert!(!turn.outcome.is_hit());
+            assert_eq!(turn.outcome == Outcome::Won, idx + 1 == solution.turns());
+            board = turn.board;
+        }
+    }
+
+    #[test]
+    fn test_solve_empty_board() {
+        // Jumping north from (6, 1) catches the bird over (6, 2)
+        let game = game(&[], &[(6, 2)]);
+        let sim = Simulation::new(&game);
+        let solution = solve(&sim).unwrap();
+        let north = Action::Move(Direction::North);
+        let jump = Action::Jump(Direction::North);
+        assert_eq!(solution.actions, vec![north, jump]);
+        replay(&sim, &solution);
+    }
+
+    #[test]
+    fn test_solve_waits_for_logs() {
+        // A log rolls down columns 5 to 8 every other turn, the bird sits in its path
+        let game = game(&[("X.", 7)], &[(6, 3), (9, 0)]);
+        let sim = Simulation::new(&game);
+        let solution = solve(&sim).unwrap();
+        replay(&sim, &solution);
+        assert!(solution.turns() > 5, "{solution:?}");
+    }
+
+    #[test]
+    fn test_solve_moving_birds() {
+        let mut game = game(&[("X.", 7)], &[(6, 4), (3, 6)]);
+        let birds = &mut game.levels[0].birds;
+        birds[0].behavior = BirdBehavior::Hop {
+            every: 1,
+            moves: vec![Direction::East, Direction::West],
+        };
+        birds[1].behavior = BirdBehavior::Circle {
+            path: vec![IVec2::new(3, 7), IVec2::new(2, 7), IVec2::new(2, 6)],
+        };
+        let sim = Simulation::new(&game);
+        let solution = solve(&sim).unwrap();
+        replay(&sim, &solution);
+    }
+
+    #[test]
+    fn test_solve_drops_heavy_bevy() {
+        // The nest is behind a wall of rocks far from the birds. Carrying all four there
+        // and putting one down to jump the wall beats walking back for the last one.
+        let mut game = game(&[], &[(4, 2), (5, 2), (6, 2), (7, 2)]);
+        let level = &mut game.levels[0];
+        level.rocks = (0..12).map(|x| IVec2::new(x, 9)).collect();
+        level.nest = Some(IVec2::new(6, 10));
+        let sim = Simulation::new(&game);
+        let solution = solve(&sim).unwrap();
+        replay(&sim, &solution);
+        assert!(solution.actions.contains(&Action::Drop), "{solution:?}");
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        // The corner bird can't be walked to, and every jump that would reach it lands off
+        // the board or takes off from a rock
+        let mut game = game(&[("X", 6)], &[(6, 0), (0, 11)]);
+        game.levels[0].rocks = vec![
+            IVec2::new(0, 10),
+            IVec2::new(1, 11),
+            IVec2::new(0, 9),
+            IVec2::new(2, 11),
+        ];
+        assert_eq!(solve(&Simulation::new(&game)), None);
+    }
+
+    #[test]
+    fn test_birdless_level_is_unsolvable() {
+        // Only banking a bird wins a level
+        let game = game(&[("X", 6)], &[]);
+        assert_eq!(solve(&Simulation::new(&game)), None);
+    }
+
+    #[test]
+    fn test_shipped_levels_are_solvable() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("assets/levels");
+        let mut checked = 0;
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if !path.to_string_lossy().ends_with(".level.ron") {
+                continue;
+            }
+            let level: Level = ron::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+            let game = Game {
+                levels: vec![level],
+                ..Default::default()
+            };
+            let sim = Simulation::new(&game);
+            let solution = solve(&sim).unwrap_or_else(|| panic!("{path:?} can't be beaten"));
+            replay(&sim, &solution);
+            checked += 1;
+        }
+        assert!(checked > 0);
+    }
+}
@@ -0,0 +1,214 @@
+//! A short text syntax for [`LogSequence`](crate::level::LogSequence) spawn patterns.
+//!
+//! Every step of the pattern is one `X` (spawn a log) or `.` (no log), followed by
+//! optional modifiers:
+//!
+//! * `+N` / `-N` shift the spawn column by `N` tiles
+//! * `^N` shifts the spawn row `N` tiles down the board (`^-N` moves it up)
+//!
+//! Whitespace is ignored, so `"X.X+1 X X .."` is a 7 step pattern.
+
+use std::fmt;
+
+use crate::level::SpawnStep;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternError {
+    /// 1 based character position in the pattern
+    pub column: usize,
+    pub kind: PatternErrorKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PatternErrorKind {
+    Empty,
+    UnexpectedChar(char),
+    /// A modifier that isn't attached to an `X` or `.`
+    ModifierWithoutStep(char),
+    /// The same modifier twice on one step
+    DuplicateModifier(char),
+    MissingNumber(char),
+    NumberTooLarge,
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: ", self.column)?;
+        match &self.kind {
+            PatternErrorKind::Empty => write!(f, "pattern has no steps"),
+            PatternErrorKind::UnexpectedChar(ch) => {
+                write!(f, "unexpected '{ch}', expected 'X', '.' or a modifier")
+            }
+            PatternErrorKind::ModifierWithoutStep(ch) => {
+                write!(f, "'{ch}' must follow an 'X' or '.'")
+            }
+            PatternErrorKind::DuplicateModifier(ch) => {
+                write!(f, "'{ch}' is given twice for the same step")
+            }
+            PatternErrorKind::MissingNumber(ch) => write!(f, "expected a number after '{ch}'"),
+            PatternErrorKind::NumberTooLarge => write!(f, "number is too large"),
+        }
+    }
+}
+
+impl std::error::Error for PatternError {}
+
+/// Parses a pattern like `"X.X+1 X X .."` into its steps.
+pub fn parse_pattern(pattern: &str) -> Result<Vec<SpawnStep>, PatternError> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut steps: Vec<SpawnStep> = Vec::new();
+    // Modifiers already used by the last step
+    let mut used = String::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let ch = chars[i];
+        let column = i + 1;
+        let error = |kind| PatternError { column, kind };
+        match ch {
+            c if c.is_whitespace() => i += 1,
+            'X' | '.' => {
+                steps.push(SpawnStep {
+                    nth_step: if ch == '.' { 0 } else { 1 },
+                    ..Default::default()
+                });
+                used.clear();
+                i += 1;
+            }
+            '+' | '-' | '^' => {
+                // `+` and `-` are the same modifier
+                let modifier = if ch == '-' { '+' } else { ch };
+                let Some(step) = steps.last_mut() else {
+                    return Err(error(PatternErrorKind::ModifierWithoutStep(ch)));
+                };
+                if used.contains(modifier) {
+                    return Err(error(PatternErrorKind::DuplicateModifier(ch)));
+                }
+                used.push(modifier);
+
+                i += 1;
+                let negative = ch == '-' || (ch == '^' && chars.get(i) == Some(&'-'));
+                if ch == '^' && negative {
+                    i += 1;
+                }
+                let start = i;
+                while i < chars.len() && chars[i].is_ascii_digit() {
+                    i += 1;
+                }
+                if start == i {
+                    return Err(error(PatternErrorKind::MissingNumber(ch)));
+                }
+                let digits: String = chars[start..i].iter().collect();
+                let value: i32 = digits
+                    .parse()
+                    .map_err(|_| error(PatternErrorKind::NumberTooLarge))?;
+                let value = if negative { -value } else { value };
+
+                if ch == '^' {
+                    step.offset_z = value;
+                } else {
+                    step.offset_x = value;
+                }
+            }
+            _ => return Err(error(PatternErrorKind::UnexpectedChar(ch))),
+        }
+    }
+
+    if steps.is_empty() {
+        return Err(PatternError {
+            column: chars.len() + 1,
+            kind: PatternErrorKind::Empty,
+        });
+    }
+    Ok(steps)
+}
+
+/// Writes steps back out in the pattern syntax, grouped in fours.
+pub fn format_pattern(steps: &[SpawnStep]) -> String {
+    let mut pattern = String::new();
+    for (i, step) in steps.iter().enumerate() {
+        if i > 0 && i % 4 == 0 {
+            pattern.push(' ');
+        }
+        if step.spawns() {
+            pattern.push('X');
+        } else {
+            pattern.push('.');
+        }
+        if step.offset_x != 0 {
+            pattern.push_str(&format!("{:+}", step.offset_x));
+        }
+        if step.offset_z != 0 {
+            pattern.push_str(&format!("^{}", step.offset_z));
+        }
+    }
+    pattern
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(nth_step: u32, offset_x: i32, offset_z: i32) -> SpawnStep {
+        SpawnStep {
+            nth_step,
+            offset_x,
+            offset_z,
+        }
+    }
+
+    #[test]
+    fn test_parse_pattern() {
+        let steps = parse_pattern("X.X+1 X X ..").unwrap();
+        assert_eq!(steps.len(), 7);
+        assert_eq!(steps[0], step(1, 0, 0));
+        assert_eq!(steps[1], step(0, 0, 0));
+        assert_eq!(steps[2], step(1, 1, 0));
+        assert_eq!(steps[6], step(0, 0, 0));
+    }
+
+    #[test]
+    fn test_parse_modifiers() {
+        let steps = parse_pattern("X-2^1 .+1^-1 X").unwrap();
+        assert_eq!(steps, vec![step(1, -2, 1), step(0, 1, -1), step(1, 0, 0)]);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = |pattern: &str| parse_pattern(pattern).unwrap_err();
+        assert_eq!(
+            err("X.Q"),
+            PatternError {
+                column: 3,
+                kind: PatternErrorKind::UnexpectedChar('Q')
+            }
+        );
+        assert_eq!(err("+1X").kind, PatternErrorKind::ModifierWithoutStep('+'));
+        assert_eq!(err("X+1-1").kind, PatternErrorKind::DuplicateModifier('-'));
+        assert_eq!(err("X+").kind, PatternErrorKind::MissingNumber('+'));
+        assert_eq!(err("X^-").kind, PatternErrorKind::MissingNumber('^'));
+        assert_eq!(err("X*2").kind, PatternErrorKind::UnexpectedChar('*'));
+        assert_eq!(err("X+99999999999").kind, PatternErrorKind::NumberTooLarge);
+        assert_eq!(err("  ").kind, PatternErrorKind::Empty);
+        assert_eq!(
+            err("X.Q").to_string(),
+            "column 3: unexpected 'Q', expected 'X', '.' or a modifier"
+        );
+    }
+
+    #[test]
+    fn test_pattern_round_trip() {
+        let steps = vec![
+            step(1, 0, 0),
+            step(0, 1, 1),
+            step(1, 0, 0),
+            step(1, 0, 0),
+            step(1, 1, 0),
+            step(1, -3, -1),
+            step(0, 0, 0),
+        ];
+        let text = format_pattern(&steps);
+        assert_eq!(text, "X.+1^1XX X+1X-3^-1.");
+        assert_eq!(parse_pattern(&text).unwrap(), steps);
+    }
+}
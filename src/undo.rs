@@ -0,0 +1,125 @@
+//! Takes turns back. The board of every turn is kept, so undoing puts an earlier board
+//! back on screen instead of playing the turns backwards.
+
+use bevy::prelude::*;
+
+use crate::{
+    BoardEntities, Game, Knockback, Player, player_translation, score::Score, simulation::Board,
+};
+
+/// What changed about the [`Game`] by the start of a turn.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub board: Board,
+    pub bevy_count: u32,
+    pub score: Score,
+}
+
+impl Snapshot {
+    pub fn new(game: &Game) -> Self {
+        Self {
+            board: game.board.clone(),
+            bevy_count: game.bevy_count,
+            score: game.score.clone(),
+        }
+    }
+}
+
+/// Every turn of the current level, the last one is the turn being played.
+#[derive(Resource, Default)]
+pub struct UndoHistory {
+    turns: Vec<Snapshot>,
+    /// Times the player went back on this level
+    pub undos: u32,
+}
+
+impl UndoHistory {
+    /// Adds the current turn of `game`, unless it is already the last one.
+    pub fn record(&mut self, game: &Game) {
+        let snapshot = Snapshot::new(game);
+        if self.turns.last() != Some(&snapshot) {
+            self.turns.push(snapshot);
+        }
+    }
+
+    /// Drops up to `turns` turns, never the first one, and returns the turn to go back
+    /// to. Returns `None` if the level has just started.
+    pub fn rewind(&mut self, turns: usize) -> Option<&Snapshot> {
+        if self.turns.len() < 2 {
+            return None;
+        }
+        let keep = self.turns.len().saturating_sub(turns).max(1);
+        self.turns.truncate(keep);
+        self.undos += 1;
+        self.turns.last()
+    }
+
+    pub fn clear(&mut self) {
+        self.turns.clear();
+        self.undos = 0;
+    }
+}
+
+pub fn record_turn(game: Res<Game>, mut history: ResMut<UndoHistory>) {
+    history.record(&game);
+}
+
+/// U takes back the last turn, Shift+U goes back to the start of the level.
+pub fn undo_turns(
+    input: Res<ButtonInput<KeyCode>>,
+    mut history: ResMut<UndoHistory>,
+    mut game: ResMut<Game>,
+    mut board_entities: BoardEntities,
+    mut player: Single<&mut Transform, (With<Player>, Without<Knockback>)>,
+) {
+    if !input.just_pressed(KeyCode::KeyU) {
+        return;
+    }
+    let turns = if input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        usize::MAX
+    } else {
+        1
+    };
+    let Some(snapshot) = history.rewind(turns).cloned() else {
+        info!("Nothing to undo");
+        return;
+    };
+    info!(
+        "Undo back to turn number: {} ({} undos)",
+        snapshot.board.step, history.undos
+    );
+
+    player.translation = player_translation(snapshot.board.player, snapshot.board.riding.is_some());
+    // The birds rescued since go back to their tiles, and the scattered ones to the bevy
+    game.bevy_count = snapshot.bevy_count;
+    game.score = snapshot.score;
+    board_entities.rebuild(&mut game, snapshot.board);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewind() {
+        let mut game = Game::default();
+        let mut history = UndoHistory::default();
+        assert!(history.rewind(1).is_none());
+
+        for step in 0..4 {
+            game.board.step = step;
+            game.bevy_count = step / 2;
+            history.record(&game);
+            // Entering the same turn twice, after a pause
+            history.record(&game);
+        }
+        assert_eq!(history.turns.len(), 4);
+
+        let snapshot = history.rewind(1).unwrap();
+        assert_eq!((snapshot.board.step, snapshot.bevy_count), (2, 1));
+        let snapshot = history.rewind(usize::MAX).unwrap();
+        assert_eq!(snapshot.board.step, 0);
+        assert!(history.rewind(1).is_none());
+        assert_eq!(history.undos, 2);
+    }
+}